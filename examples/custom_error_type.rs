@@ -10,7 +10,7 @@ enum MyValidationError {
 // Define a validator using String as error type
 fn is_positive(i: i32) -> Result<i32, MyValidationError> {
     match i {
-        i if i == 0 => Err(MyValidationError::ZeroError),
+        0 => Err(MyValidationError::ZeroError),
         i if i < 0 => Err(MyValidationError::NegativeError),
         i => Ok(i),
     }
@@ -36,6 +36,7 @@ fn main() {
         let instance = A::try_from(UnvalidatedA { a });
         assert!(instance
             .expect_err("should fail")
+            .into_vec()
             .contains(&MyValidationError::ZeroError))
     }
 }
@@ -0,0 +1,42 @@
+//! Support types of the builders generated with `#[validation(builder)]`
+
+use std::fmt;
+
+/// A required field was not set on the builder of an unvalidated struct
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct MissingField(pub &'static str);
+
+impl fmt::Display for MissingField {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "missing field `{}`", self.0)
+    }
+}
+
+impl std::error::Error for MissingField {}
+
+/// Failure of `validate()` on the builder of an unvalidated struct
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum BuilderError<E> {
+    /// A required field was not set
+    MissingField(MissingField),
+    /// All the required fields were set, but the validation failed
+    Invalid(E),
+}
+
+impl<E> From<MissingField> for BuilderError<E> {
+    fn from(value: MissingField) -> Self {
+        BuilderError::MissingField(value)
+    }
+}
+
+/// State of a required field that was not set yet on a typestate builder
+///
+/// Generated with `#[validation(builder = typestate)]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Unset;
+
+/// State of a required field that was set on a typestate builder
+///
+/// Generated with `#[validation(builder = typestate)]`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Set<T>(pub T);
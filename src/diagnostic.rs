@@ -0,0 +1,569 @@
+//! Diagnostics pointing at the failed values in the validated source text
+//!
+//! Available with the `miette` feature. When the unvalidated struct is deserialized from a
+//! config file, [`Spans`] maps the [paths][FieldPath] of its values to their byte spans in the
+//! text. [`ValidationReport`] then implements [`miette::Diagnostic`], labelling each failure at
+//! the offending value, so it renders like a compiler error.
+//!
+//! The spans of a JSON text are found with [`Spans::from_json`], the ones of a TOML text with
+//! `Spans::from_toml`, which is available with the `toml` feature and reads them from the
+//! document parsed by `toml_edit`. For other formats they are inserted one by one with
+//! [`Spans::insert`], from wherever the deserializer of the format reports them.
+
+use std::{collections::HashMap, fmt};
+
+use miette::{Diagnostic, LabeledSpan, NamedSource, SourceSpan};
+
+use crate::error::{FieldPath, PathSegment, ValidationErrors};
+
+/// Byte spans of the values in the source text, by their paths
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Spans {
+    spans: HashMap<FieldPath, SourceSpan>,
+}
+
+impl Spans {
+    /// No spans yet
+    pub fn new() -> Self {
+        Spans::default()
+    }
+
+    /// Sets the span of the value at `path`
+    pub fn insert(&mut self, path: FieldPath, span: impl Into<SourceSpan>) {
+        self.spans.insert(path, span.into());
+    }
+
+    /// The span of the value at `path`, or of the closest value containing it
+    pub fn get(&self, path: &FieldPath) -> Option<SourceSpan> {
+        (0..=path.segments().len()).rev().find_map(|len| {
+            self.spans
+                .get(&FieldPath::new(path.segments()[..len].to_vec()))
+                .copied()
+        })
+    }
+
+    /// The spans of all the values of a JSON text, `None` when it is not valid JSON
+    ///
+    /// Texts nesting arrays and objects deeper than 128 levels are rejected as well.
+    pub fn from_json(source: &str) -> Option<Self> {
+        let mut scanner = JsonScanner {
+            source: source.as_bytes(),
+            position: 0,
+            depth: 0,
+            path: Vec::new(),
+            spans: Spans::new(),
+        };
+        scanner.value()?;
+        scanner.whitespace();
+        (scanner.position == source.len()).then_some(scanner.spans)
+    }
+
+    /// The spans of all the values of a TOML text, `None` when it is not valid TOML
+    ///
+    /// Tables span their header together with their keys, the top-level table the whole text.
+    #[cfg(feature = "toml")]
+    pub fn from_toml(source: &str) -> Option<Self> {
+        let document = toml_edit::ImDocument::parse(source).ok()?;
+        let mut spans = Spans::new();
+        spans.insert_toml_table(document.as_table(), &mut Vec::new());
+        spans.insert(FieldPath::default(), (0, source.len()));
+        Some(spans)
+    }
+
+    /// Inserts the span of the TOML `table` at `path`, and of all the values it contains
+    #[cfg(feature = "toml")]
+    fn insert_toml_table(&mut self, table: &toml_edit::Table, path: &mut Vec<PathSegment>) {
+        use toml_edit::Item;
+
+        self.insert_toml_span(table.span(), path);
+        for (key, item) in table.iter() {
+            path.push(PathSegment::Field(key.to_string().into()));
+            match item {
+                Item::None => {}
+                Item::Value(value) => self.insert_toml_value(value, path),
+                Item::Table(table) => self.insert_toml_table(table, path),
+                Item::ArrayOfTables(tables) => {
+                    self.insert_toml_span(tables.span(), path);
+                    for (i, table) in tables.iter().enumerate() {
+                        path.push(PathSegment::Index(i));
+                        self.insert_toml_table(table, path);
+                        path.pop();
+                    }
+                }
+            }
+            path.pop();
+        }
+    }
+
+    /// Inserts the span of the TOML `value` at `path`, and of all the values it contains
+    #[cfg(feature = "toml")]
+    fn insert_toml_value(&mut self, value: &toml_edit::Value, path: &mut Vec<PathSegment>) {
+        use toml_edit::Value;
+
+        self.insert_toml_span(value.span(), path);
+        match value {
+            Value::Array(array) => {
+                for (i, value) in array.iter().enumerate() {
+                    path.push(PathSegment::Index(i));
+                    self.insert_toml_value(value, path);
+                    path.pop();
+                }
+            }
+            Value::InlineTable(table) => {
+                for (key, value) in table.iter() {
+                    path.push(PathSegment::Field(key.to_string().into()));
+                    self.insert_toml_value(value, path);
+                    path.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+
+    #[cfg(feature = "toml")]
+    fn insert_toml_span(&mut self, span: Option<std::ops::Range<usize>>, path: &[PathSegment]) {
+        if let Some(span) = span {
+            self.insert(FieldPath::new(path.to_vec()), span.start..span.end);
+        }
+    }
+}
+
+/// Deepest nesting of arrays and objects [`Spans::from_json`] accepts
+const MAX_JSON_DEPTH: usize = 128;
+
+/// Walks a JSON text recording the span of every value
+struct JsonScanner<'a> {
+    source: &'a [u8],
+    position: usize,
+    /// Number of arrays and objects the scanner is in
+    depth: usize,
+    path: Vec<PathSegment>,
+    spans: Spans,
+}
+
+impl JsonScanner<'_> {
+    fn whitespace(&mut self) {
+        while self
+            .source
+            .get(self.position)
+            .is_some_and(|b| b.is_ascii_whitespace())
+        {
+            self.position += 1;
+        }
+    }
+
+    fn eat(&mut self, byte: u8) -> Option<()> {
+        self.whitespace();
+        (self.source.get(self.position) == Some(&byte)).then(|| self.position += 1)
+    }
+
+    fn peek(&mut self) -> Option<u8> {
+        self.whitespace();
+        self.source.get(self.position).copied()
+    }
+
+    fn value(&mut self) -> Option<()> {
+        self.whitespace();
+        let start = self.position;
+        match self.peek()? {
+            b'{' => self.nested(Self::object)?,
+            b'[' => self.nested(Self::array)?,
+            b'"' => {
+                self.string()?;
+            }
+            _ => self.scalar()?,
+        }
+        self.spans.insert(
+            FieldPath::new(self.path.clone()),
+            (start, self.position - start),
+        );
+        Some(())
+    }
+
+    /// Scans an array or an object one level deeper, failing past [`MAX_JSON_DEPTH`]
+    fn nested(&mut self, scan: fn(&mut Self) -> Option<()>) -> Option<()> {
+        if self.depth == MAX_JSON_DEPTH {
+            return None;
+        }
+        self.depth += 1;
+        scan(self)?;
+        self.depth -= 1;
+        Some(())
+    }
+
+    fn object(&mut self) -> Option<()> {
+        self.eat(b'{')?;
+        if self.eat(b'}').is_some() {
+            return Some(());
+        }
+        loop {
+            self.whitespace();
+            let key = self.string()?;
+            self.eat(b':')?;
+            self.path.push(PathSegment::Field(key.into()));
+            self.value()?;
+            self.path.pop();
+            if self.eat(b'}').is_some() {
+                return Some(());
+            }
+            self.eat(b',')?;
+        }
+    }
+
+    fn array(&mut self) -> Option<()> {
+        self.eat(b'[')?;
+        if self.eat(b']').is_some() {
+            return Some(());
+        }
+        for i in 0.. {
+            self.path.push(PathSegment::Index(i));
+            self.value()?;
+            self.path.pop();
+            if self.eat(b']').is_some() {
+                break;
+            }
+            self.eat(b',')?;
+        }
+        Some(())
+    }
+
+    /// Scans a string, yielding its contents with the escapes resolved
+    fn string(&mut self) -> Option<String> {
+        self.eat(b'"')?;
+        let mut contents = Vec::new();
+        loop {
+            let byte = *self.source.get(self.position)?;
+            self.position += 1;
+            match byte {
+                b'"' => break,
+                b'\\' => {
+                    let escape = *self.source.get(self.position)?;
+                    self.position += 1;
+                    let c = match escape {
+                        b'"' | b'\\' | b'/' => escape as char,
+                        b'b' => '\u{8}',
+                        b'f' => '\u{c}',
+                        b'n' => '\n',
+                        b'r' => '\r',
+                        b't' => '\t',
+                        b'u' => self.unicode_escape()?,
+                        _ => return None,
+                    };
+                    contents.extend_from_slice(c.encode_utf8(&mut [0; 4]).as_bytes());
+                }
+                0..=0x1f => return None,
+                _ => contents.push(byte),
+            }
+        }
+        String::from_utf8(contents).ok()
+    }
+
+    /// Scans the code point of a `\u` escape, following it to the low surrogate of a pair
+    fn unicode_escape(&mut self) -> Option<char> {
+        let high = self.hex4()?;
+        if !(0xd800..0xdc00).contains(&high) {
+            return char::from_u32(high);
+        }
+        if self.source.get(self.position..self.position + 2)? != b"\\u" {
+            return None;
+        }
+        self.position += 2;
+        let low = self.hex4()?;
+        if !(0xdc00..0xe000).contains(&low) {
+            return None;
+        }
+        char::from_u32(0x10000 + ((high - 0xd800) << 10) + (low - 0xdc00))
+    }
+
+    fn hex4(&mut self) -> Option<u32> {
+        let digits = self.source.get(self.position..self.position + 4)?;
+        let digits = std::str::from_utf8(digits).ok()?;
+        if !digits.bytes().all(|b| b.is_ascii_hexdigit()) {
+            return None;
+        }
+        self.position += 4;
+        u32::from_str_radix(digits, 16).ok()
+    }
+
+    /// Scans a number, `true`, `false` or `null`
+    fn scalar(&mut self) -> Option<()> {
+        for literal in [&b"true"[..], b"false", b"null"] {
+            if self.source[self.position..].starts_with(literal) {
+                self.position += literal.len();
+                return Some(());
+            }
+        }
+        self.number()
+    }
+
+    /// Scans a number, `-`, an integer part without leading zeros, a fraction and an exponent
+    fn number(&mut self) -> Option<()> {
+        self.optional(b"-");
+        match self.source.get(self.position)? {
+            b'0' => self.position += 1,
+            b'1'..=b'9' => self.digits(),
+            _ => return None,
+        }
+        if self.optional(b".") {
+            self.required_digits()?;
+        }
+        if self.optional(b"eE") {
+            self.optional(b"+-");
+            self.required_digits()?;
+        }
+        Some(())
+    }
+
+    /// Skips a byte that is one of `bytes`, if there is one
+    fn optional(&mut self, bytes: &[u8]) -> bool {
+        let present = self
+            .source
+            .get(self.position)
+            .is_some_and(|b| bytes.contains(b));
+        if present {
+            self.position += 1;
+        }
+        present
+    }
+
+    fn digits(&mut self) {
+        while self
+            .source
+            .get(self.position)
+            .is_some_and(|b| b.is_ascii_digit())
+        {
+            self.position += 1;
+        }
+    }
+
+    fn required_digits(&mut self) -> Option<()> {
+        let start = self.position;
+        self.digits();
+        (self.position > start).then_some(())
+    }
+}
+
+/// The failures of a validation as a diagnostic, labelled at the values in the source text
+///
+/// Failures are labelled at their value, or at the closest value containing it, e.g. the object
+/// missing a field. Failures without any span are listed in the help.
+///
+/// ```
+/// use valibuk::diagnostic::{Spans, ValidationReport};
+/// use valibuk::Validated;
+///
+/// fn is_positive(i: i32) -> Result<i32, String> {
+///     if i > 0 {
+///         Ok(i)
+///     } else {
+///         Err("must be positive".to_string())
+///     }
+/// }
+///
+/// #[derive(Validated, Debug)]
+/// struct A {
+///     #[validator(is_positive)]
+///     age: i32,
+/// }
+///
+/// let source = r#"{ "age": -1 }"#;
+/// // parsed from `source` by e.g. serde_json
+/// let errors = A::try_from(UnvalidatedA { age: -1 }).unwrap_err();
+/// // or `Spans::from_toml` for TOML texts, with the `toml` feature
+/// let spans = Spans::from_json(source).expect("valid JSON");
+/// let report = miette::Report::new(ValidationReport::new(&errors, "a.json", source, &spans));
+/// println!("{:?}", report);
+/// ```
+#[derive(Debug)]
+pub struct ValidationReport {
+    source: NamedSource<String>,
+    failures: usize,
+    labels: Vec<LabeledSpan>,
+    help: Option<String>,
+}
+
+impl ValidationReport {
+    /// Report of `errors` in the text of the file `name`, whose values are located by `spans`
+    pub fn new<E: fmt::Display>(
+        errors: &ValidationErrors<E>,
+        name: impl AsRef<str>,
+        source: impl Into<String>,
+        spans: &Spans,
+    ) -> Self {
+        let mut labels = Vec::new();
+        let mut unlocated = Vec::new();
+        for e in errors {
+            match spans.get(&e.path) {
+                Some(span) => labels.push(LabeledSpan::new_with_span(
+                    Some(format!("{}: {}", e.path, e.error)),
+                    span,
+                )),
+                None => unlocated.push(e.to_string()),
+            }
+        }
+        if errors.omitted() > 0 {
+            unlocated.push(format!("{} more errors", errors.omitted()));
+        }
+        ValidationReport {
+            source: NamedSource::new(name, source.into()),
+            failures: errors.len() + errors.omitted(),
+            labels,
+            help: (!unlocated.is_empty()).then(|| unlocated.join("\n")),
+        }
+    }
+}
+
+impl fmt::Display for ValidationReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.failures {
+            1 => write!(f, "validation failed"),
+            n => write!(f, "validation failed {} times", n),
+        }
+    }
+}
+
+impl std::error::Error for ValidationReport {}
+
+impl Diagnostic for ValidationReport {
+    fn code<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        Some(Box::new("valibuk::validation"))
+    }
+
+    fn source_code(&self) -> Option<&dyn miette::SourceCode> {
+        Some(&self.source)
+    }
+
+    fn labels(&self) -> Option<Box<dyn Iterator<Item = LabeledSpan> + '_>> {
+        Some(Box::new(self.labels.iter().cloned()))
+    }
+
+    fn help<'a>(&'a self) -> Option<Box<dyn fmt::Display + 'a>> {
+        self.help
+            .as_ref()
+            .map(|h| Box::new(h) as Box<dyn fmt::Display + 'a>)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_json_spans() {
+        let source = r#"{"age": -1, "address": {"lines": ["a", ""]}}"#;
+        let spans = Spans::from_json(source).expect("valid JSON");
+        let span = |path: Vec<PathSegment>| {
+            let span = spans.get(&FieldPath::new(path)).expect("span");
+            &source[span.offset()..span.offset() + span.len()]
+        };
+        assert_eq!(span(vec!["age".into()]), "-1");
+        assert_eq!(
+            span(vec!["address".into(), "lines".into(), 1.into()]),
+            r#""""#
+        );
+        assert_eq!(
+            span(vec!["address".into(), "lines".into(), 5.into()]),
+            r#"["a", ""]"#
+        );
+        assert_eq!(Spans::from_json(r#"{"age": }"#), None);
+    }
+
+    #[test]
+    fn test_json_strictness() {
+        for valid in [
+            "-0.5e+10",
+            "[true, false, null, 12, 1E3]",
+            r#"{"a\"b\u00e9\ud83d\ude00": 1}"#,
+        ] {
+            assert!(Spans::from_json(valid).is_some(), "{}", valid);
+        }
+        for invalid in [
+            r#"{"a": hello}"#,
+            "[truex]",
+            "01",
+            "1.",
+            "-",
+            "1e",
+            r#""\x""#,
+            r#""\ud83d""#,
+            "\"a\nb\"",
+        ] {
+            assert_eq!(Spans::from_json(invalid), None, "{}", invalid);
+        }
+    }
+
+    #[test]
+    fn test_json_escaped_keys() {
+        let source = r#"{"a\"b": 1, "\u00e9": 2}"#;
+        let spans = Spans::from_json(source).expect("valid JSON");
+        let key = |k: &str| FieldPath::new(vec![PathSegment::Field(k.to_string().into())]);
+        assert_eq!(spans.get(&key("a\"b")).map(|s| s.offset()), Some(9));
+        assert_eq!(spans.get(&key("é")).map(|s| s.offset()), Some(22));
+    }
+
+    #[test]
+    fn test_json_depth() {
+        let nested = |depth| "[".repeat(depth) + &"]".repeat(depth);
+        assert!(Spans::from_json(&nested(MAX_JSON_DEPTH)).is_some());
+        assert_eq!(Spans::from_json(&nested(MAX_JSON_DEPTH + 1)), None);
+        assert_eq!(Spans::from_json(&nested(100_000)), None);
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_spans() {
+        let source = "age = -1\n\"a\\\"b\" = 2\n\n[address]\nlines = [\"a\", \"\"]\n\n[[rows]]\nn = { x = 3 }\n";
+        let spans = Spans::from_toml(source).expect("valid TOML");
+        let span = |path: Vec<PathSegment>| {
+            let span = spans.get(&FieldPath::new(path)).expect("span");
+            &source[span.offset()..span.offset() + span.len()]
+        };
+        assert_eq!(span(vec!["age".into()]), "-1");
+        assert_eq!(
+            span(vec![PathSegment::Field("a\"b".to_string().into())]),
+            "2"
+        );
+        assert_eq!(
+            span(vec!["address".into(), "lines".into(), 1.into()]),
+            r#""""#
+        );
+        assert_eq!(
+            span(vec!["address".into()]),
+            "[address]\nlines = [\"a\", \"\"]"
+        );
+        assert_eq!(
+            span(vec!["rows".into(), 0.into(), "n".into(), "x".into()]),
+            "3"
+        );
+        // missing values are labelled at the closest value containing them
+        assert_eq!(span(vec!["name".into()]), source);
+        assert_eq!(Spans::from_toml("age = "), None);
+    }
+
+    #[test]
+    fn test_report() {
+        let source = r#"{"age": -1}"#;
+        let mut errors = ValidationErrors::new();
+        errors.push("age", "must be positive".to_string());
+        errors.push("name", "missing".to_string());
+        let report = ValidationReport::new(
+            &errors,
+            "a.json",
+            source,
+            &Spans::from_json(source).unwrap(),
+        );
+        assert_eq!(report.to_string(), "validation failed 2 times");
+        let labels: Vec<_> = report.labels().unwrap().collect();
+        assert_eq!(labels[0].label(), Some("age: must be positive"));
+        assert_eq!((labels[0].offset(), labels[0].len()), (8, 2));
+        // missing values are labelled at the object that should contain them
+        assert_eq!(labels[1].label(), Some("name: missing"));
+        assert_eq!((labels[1].offset(), labels[1].len()), (0, source.len()));
+        assert!(report.help().is_none());
+        let report = ValidationReport::new(&errors, "a.json", source, &Spans::new());
+        assert_eq!(
+            report.help().unwrap().to_string(),
+            "age: must be positive\nname: missing"
+        );
+    }
+}
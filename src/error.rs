@@ -0,0 +1,507 @@
+//! The error type of the generated validation entry points
+
+use std::{
+    borrow::Cow,
+    collections::{hash_map::DefaultHasher, HashMap},
+    fmt,
+    hash::{Hash, Hasher},
+};
+
+/// Step of a [`FieldPath`], the name of a field or the index of an element of a collection
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum PathSegment {
+    /// Name of a field
+    Field(Cow<'static, str>),
+    /// Index of an element of a collection
+    Index(usize),
+}
+
+impl fmt::Display for PathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PathSegment::Field(name) => f.write_str(name),
+            PathSegment::Index(i) => write!(f, "{}", i),
+        }
+    }
+}
+
+impl From<&'static str> for PathSegment {
+    fn from(value: &'static str) -> Self {
+        PathSegment::Field(Cow::Borrowed(value))
+    }
+}
+
+impl From<usize> for PathSegment {
+    fn from(value: usize) -> Self {
+        PathSegment::Index(value)
+    }
+}
+
+/// Location of a failure, e.g. `address`, `lines`, `2` for the third line of the address
+///
+/// It displays in the dotted form, `address.lines.2`.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Hash)]
+pub struct FieldPath {
+    segments: Vec<PathSegment>,
+}
+
+impl FieldPath {
+    /// Path of the given segments, outermost first
+    pub fn new(segments: Vec<PathSegment>) -> Self {
+        FieldPath { segments }
+    }
+
+    /// The segments of the path, outermost first
+    pub fn segments(&self) -> &[PathSegment] {
+        &self.segments
+    }
+
+    /// The outermost segment, i.e. the field of the validated struct
+    pub fn first(&self) -> Option<&PathSegment> {
+        self.segments.first()
+    }
+
+    /// Prepends `segment`, used when the errors of a nested value are reported by its parent
+    pub fn prepend(&mut self, segment: PathSegment) {
+        self.segments.insert(0, segment);
+    }
+
+    /// Renders the path as a JSON Pointer (RFC 6901), e.g. `/address/lines/2`
+    pub fn to_json_pointer(&self) -> String {
+        self.segments
+            .iter()
+            .map(|s| format!("/{}", s.to_string().replace('~', "~0").replace('/', "~1")))
+            .collect()
+    }
+
+    /// Renders the path in the dotted form, e.g. `address.lines.2`
+    pub fn to_dotted(&self) -> String {
+        self.to_string()
+    }
+}
+
+impl fmt::Display for FieldPath {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.segments.iter().enumerate() {
+            if i > 0 {
+                f.write_str(".")?;
+            }
+            write!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<&'static str> for FieldPath {
+    fn from(value: &'static str) -> Self {
+        FieldPath::new(vec![value.into()])
+    }
+}
+
+impl From<usize> for FieldPath {
+    fn from(value: usize) -> Self {
+        FieldPath::new(vec![value.into()])
+    }
+}
+
+/// Compares with the dotted form
+impl PartialEq<str> for FieldPath {
+    fn eq(&self, other: &str) -> bool {
+        self.to_dotted() == other
+    }
+}
+
+impl PartialEq<&str> for FieldPath {
+    fn eq(&self, other: &&str) -> bool {
+        self == *other
+    }
+}
+
+/// Failure of the validator of a single field
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FieldError<E> {
+    /// Location of the value that failed to validate, the name of the field unless the error
+    /// comes from a nested value
+    pub path: FieldPath,
+    /// The error returned by the validator
+    pub error: E,
+    /// Rendering of the rejected value, for validators with `capture_value`
+    ///
+    /// Values of fields marked with `#[sensitive]` are replaced with [`REDACTED`].
+    pub value: Option<String>,
+}
+
+impl<E: fmt::Display> fmt::Display for FieldError<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}: {}", self.path, self.error)?;
+        if let Some(value) = &self.value {
+            write!(f, " (got {})", value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Placeholder captured instead of the values of `#[sensitive]` fields
+pub const REDACTED: &str = "[redacted]";
+
+/// Renders the rejected value of a field with `capture_value`
+pub fn capture_value<T: fmt::Debug + ?Sized>(value: &T) -> String {
+    format!("{:?}", value)
+}
+
+/// Stands in for [`capture_value`] on `#[sensitive]` fields, the value is never rendered
+pub fn redact_value<T: ?Sized>(_value: &T) -> String {
+    REDACTED.to_string()
+}
+
+/// All the failures of a validation, in field order
+///
+/// This is the error of `TryFrom` and the other validation entry points. It implements
+/// [`std::error::Error`], so it can be propagated with `?` into e.g. `Box<dyn Error>`, and
+/// displays as one line per failure, prefixed with the name of the field. The raw errors of the
+/// validators can be taken out with [`into_vec`][Self::into_vec] or by iterating over it.
+///
+/// The number of recorded failures can be capped with [`with_limit`][Self::with_limit] and
+/// identical errors of a field can be recorded once with [`deduplicated`][Self::deduplicated],
+/// the failures left out are only [counted][Self::omitted].
+#[derive(Debug, Clone)]
+pub struct ValidationErrors<E> {
+    errors: Vec<FieldError<E>>,
+    limit: Option<usize>,
+    dedup: Option<Dedup<E>>,
+    omitted: usize,
+}
+
+/// The failures recorded so far, by the hash of their field and error
+#[derive(Debug, Clone)]
+struct Dedup<E> {
+    hash: fn(&FieldError<E>) -> u64,
+    same: fn(&FieldError<E>, &FieldError<E>) -> bool,
+    seen: HashMap<u64, Vec<usize>>,
+}
+
+/// Hashes the field of the failure, i.e. the first segment of its path, with its error
+fn dedup_hash<E: Hash>(error: &FieldError<E>) -> u64 {
+    let mut hasher = DefaultHasher::new();
+    error.path.first().hash(&mut hasher);
+    error.error.hash(&mut hasher);
+    hasher.finish()
+}
+
+/// True when both failures are of the same field with the same error
+fn dedup_same<E: Eq>(a: &FieldError<E>, b: &FieldError<E>) -> bool {
+    a.path.first() == b.path.first() && a.error == b.error
+}
+
+impl<E> ValidationErrors<E> {
+    /// No errors yet
+    pub fn new() -> Self {
+        ValidationErrors {
+            errors: Vec::new(),
+            limit: None,
+            dedup: None,
+            omitted: 0,
+        }
+    }
+
+    /// Records at most `limit` failures, the ones after that are only counted
+    pub fn with_limit(mut self, limit: usize) -> Self {
+        self.limit = Some(limit);
+        self
+    }
+
+    /// Number of failures left out, over the limit or identical to one already recorded
+    pub fn omitted(&self) -> usize {
+        self.omitted
+    }
+
+    /// Records the failure unless it is over the limit or a duplicate
+    fn record(&mut self, error: FieldError<E>) {
+        if self.limit.is_some_and(|limit| self.errors.len() >= limit) {
+            self.omitted += 1;
+            return;
+        }
+        if let Some(dedup) = &mut self.dedup {
+            let seen = dedup.seen.entry((dedup.hash)(&error)).or_default();
+            if seen.iter().any(|&i| (dedup.same)(&self.errors[i], &error)) {
+                self.omitted += 1;
+                return;
+            }
+            seen.push(self.errors.len());
+        }
+        self.errors.push(error);
+    }
+
+    /// Records the failure of the validator of `field`
+    pub fn push(&mut self, field: &'static str, error: E) {
+        self.push_at(field.into(), error);
+    }
+
+    /// Records the failure of the validator of `field` together with the rejected value
+    pub fn push_with_value(&mut self, field: &'static str, error: E, value: Option<String>) {
+        self.record(FieldError {
+            path: field.into(),
+            error,
+            value,
+        });
+    }
+
+    /// Records a failure at `path`
+    pub fn push_at(&mut self, path: FieldPath, error: E) {
+        self.record(FieldError {
+            path,
+            error,
+            value: None,
+        });
+    }
+
+    /// Records the failures of a nested value under `segment`, e.g. the errors of the nested
+    /// struct in field `address` or of the element at some index of a collection
+    pub fn nest(&mut self, segment: impl Into<PathSegment>, nested: ValidationErrors<E>) {
+        let segment = segment.into();
+        self.omitted += nested.omitted;
+        for mut e in nested.errors {
+            e.path.prepend(segment.clone());
+            self.record(e);
+        }
+    }
+
+    /// Replaces all the captured values with [`REDACTED`]
+    ///
+    /// Used for the errors of a nested value held by a `#[sensitive]` field, whose own fields may
+    /// capture their values.
+    pub fn redacted(mut self) -> Self {
+        for e in &mut self.errors {
+            if e.value.is_some() {
+                e.value = Some(REDACTED.to_string());
+            }
+        }
+        self
+    }
+
+    /// Converts the errors, keeping their paths
+    ///
+    /// The limit is kept, identical converted errors are no longer deduplicated.
+    pub fn map<F>(self, mut f: impl FnMut(E) -> F) -> ValidationErrors<F> {
+        ValidationErrors {
+            errors: self
+                .errors
+                .into_iter()
+                .map(|e| FieldError {
+                    path: e.path,
+                    error: f(e.error),
+                    value: e.value,
+                })
+                .collect(),
+            limit: self.limit,
+            dedup: None,
+            omitted: self.omitted,
+        }
+    }
+
+    /// Number of recorded failures
+    pub fn len(&self) -> usize {
+        self.errors.len()
+    }
+
+    /// True when nothing failed
+    pub fn is_empty(&self) -> bool {
+        self.errors.is_empty() && self.omitted == 0
+    }
+
+    /// The failures together with their paths
+    pub fn iter(&self) -> std::slice::Iter<'_, FieldError<E>> {
+        self.errors.iter()
+    }
+
+    /// The failures of `field`, including those of the values nested in it
+    pub fn field<'a>(&'a self, field: &'a str) -> impl Iterator<Item = &'a E> + 'a {
+        self.errors
+            .iter()
+            .filter(move |e| matches!(e.path.first(), Some(PathSegment::Field(f)) if f == field))
+            .map(|e| &e.error)
+    }
+
+    /// The failure at `path`, given in the dotted form, e.g. `address.lines.2`
+    pub fn at<'a>(&'a self, path: &'a str) -> impl Iterator<Item = &'a E> + 'a {
+        self.errors
+            .iter()
+            .filter(move |e| e.path == path)
+            .map(|e| &e.error)
+    }
+
+    /// The raw errors of the validators, without their paths
+    pub fn into_vec(self) -> Vec<E> {
+        self.errors.into_iter().map(|e| e.error).collect()
+    }
+
+    /// The failures together with their paths
+    pub fn into_field_errors(self) -> Vec<FieldError<E>> {
+        self.errors
+    }
+}
+
+impl<E: Hash + Eq> ValidationErrors<E> {
+    /// Records identical errors of a field once, at the path of their first failure
+    ///
+    /// Errors of a nested value, e.g. of the elements of a collection, belong to the field
+    /// holding it. Identical errors of different fields are all recorded.
+    pub fn deduplicated(mut self) -> Self {
+        self.dedup = Some(Dedup {
+            hash: dedup_hash,
+            same: dedup_same,
+            seen: HashMap::new(),
+        });
+        self
+    }
+}
+
+/// Compares the recorded failures and the number of the omitted ones
+impl<E: PartialEq> PartialEq for ValidationErrors<E> {
+    fn eq(&self, other: &Self) -> bool {
+        self.errors == other.errors && self.omitted == other.omitted
+    }
+}
+
+impl<E: Eq> Eq for ValidationErrors<E> {}
+
+impl<E> Default for ValidationErrors<E> {
+    fn default() -> Self {
+        ValidationErrors::new()
+    }
+}
+
+impl<E: fmt::Display> fmt::Display for ValidationErrors<E> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, error) in self.errors.iter().enumerate() {
+            if i > 0 {
+                writeln!(f)?;
+            }
+            write!(f, "{}", error)?;
+        }
+        if self.omitted > 0 {
+            if !self.errors.is_empty() {
+                writeln!(f)?;
+            }
+            write!(f, "{} more errors", self.omitted)?;
+        }
+        Ok(())
+    }
+}
+
+impl<E: fmt::Debug + fmt::Display> std::error::Error for ValidationErrors<E> {}
+
+impl<E> IntoIterator for ValidationErrors<E> {
+    type Item = E;
+    type IntoIter = std::iter::Map<std::vec::IntoIter<FieldError<E>>, fn(FieldError<E>) -> E>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.into_iter().map(|e| e.error)
+    }
+}
+
+impl<'a, E> IntoIterator for &'a ValidationErrors<E> {
+    type Item = &'a FieldError<E>;
+    type IntoIter = std::slice::Iter<'a, FieldError<E>>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.errors.iter()
+    }
+}
+
+impl<E> From<ValidationErrors<E>> for Vec<E> {
+    fn from(value: ValidationErrors<E>) -> Self {
+        value.into_vec()
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_display() {
+        let mut errors = ValidationErrors::new();
+        errors.push("email", "not an email".to_string());
+        errors.push("age", "wrong".to_string());
+        assert_eq!(errors.to_string(), "email: not an email\nage: wrong");
+        assert_eq!(errors.field("age").collect::<Vec<_>>(), vec!["wrong"]);
+        assert_eq!(
+            errors.into_vec(),
+            vec!["not an email".to_string(), "wrong".to_string()]
+        );
+    }
+
+    #[test]
+    fn test_nested_paths() {
+        let mut lines = ValidationErrors::new();
+        lines.push_at(2.into(), "empty".to_string());
+        let mut address = ValidationErrors::new();
+        address.nest("lines", lines);
+        let mut errors = ValidationErrors::new();
+        errors.push("name", "wrong".to_string());
+        errors.nest("address", address);
+        let paths: Vec<_> = errors.iter().map(|e| e.path.to_json_pointer()).collect();
+        assert_eq!(paths, vec!["/name", "/address/lines/2"]);
+        assert_eq!(errors.to_string(), "name: wrong\naddress.lines.2: empty");
+        assert_eq!(errors.field("address").count(), 1);
+        assert_eq!(errors.at("address.lines.2").count(), 1);
+        assert_eq!(
+            FieldPath::new(vec!["a/b~c".into()]).to_json_pointer(),
+            "/a~1b~0c"
+        );
+    }
+
+    #[test]
+    fn test_limit_and_deduplication() {
+        let mut errors = ValidationErrors::new().with_limit(2).deduplicated();
+        for i in 0..5 {
+            let mut row = ValidationErrors::new();
+            row.push("a", format!("wrong {}", i % 3));
+            errors.nest(i, row);
+        }
+        assert_eq!(errors.len(), 2);
+        assert_eq!(errors.omitted(), 3);
+        assert_eq!(
+            errors.to_string(),
+            "0.a: wrong 0\n1.a: wrong 1\n3 more errors"
+        );
+        let mut errors = ValidationErrors::new().deduplicated();
+        for field in ["a", "b", "a", "b"] {
+            let mut row = ValidationErrors::new();
+            row.push("c", "wrong".to_string());
+            errors.nest(field, row);
+        }
+        assert_eq!(errors.to_string(), "a.c: wrong\nb.c: wrong\n2 more errors");
+    }
+
+    #[test]
+    fn test_captured_value() {
+        let mut errors = ValidationErrors::new();
+        errors.push_with_value("age", "wrong".to_string(), Some(capture_value(&-1)));
+        errors.push_with_value("password", "short".to_string(), Some(redact_value("abc")));
+        assert_eq!(
+            errors.to_string(),
+            "age: wrong (got -1)\npassword: short (got [redacted])"
+        );
+        let mut nested = ValidationErrors::new();
+        nested.push_with_value("pin", "short".to_string(), Some(capture_value("12")));
+        nested.push("user", "missing".to_string());
+        let mut errors = ValidationErrors::new();
+        errors.nest("creds", nested.redacted());
+        assert_eq!(
+            errors.to_string(),
+            "creds.pin: short (got [redacted])\ncreds.user: missing"
+        );
+    }
+
+    #[test]
+    fn test_into_box_dyn_error() {
+        fn validate() -> Result<(), Box<dyn std::error::Error>> {
+            let mut errors = ValidationErrors::new();
+            errors.push("a", "wrong".to_string());
+            Err(errors)?
+        }
+        assert_eq!(validate().unwrap_err().to_string(), "a: wrong");
+    }
+}
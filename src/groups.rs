@@ -0,0 +1,29 @@
+//! Validation groups for `#[validator(..., groups(...))]`
+//!
+//! Validators with groups only run in `A::validate_for::<G>(unvalidated)` when `G` is one of
+//! their groups, validators without groups always run. A group is any type implementing
+//! [`Group`], listed in `groups(...)` by its path, e.g. `groups(Create, my::Publish)`, so a
+//! misspelled group fails to compile.
+
+use std::any::TypeId;
+
+/// A set of rules validators can be assigned to with `groups(...)`
+pub trait Group: 'static {}
+
+/// Group of rules for creating a new record, `groups(Create)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Create;
+
+impl Group for Create {}
+
+/// Group of rules for updating an existing record, `groups(Update)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Update;
+
+impl Group for Update {}
+
+/// True when `G`, the group validated for, is the group `H` listed in `groups(...)`
+#[doc(hidden)]
+pub fn is_group<G: Group, H: Group>() -> bool {
+    TypeId::of::<G>() == TypeId::of::<H>()
+}
@@ -0,0 +1,224 @@
+//! Localized validation messages from message catalogs
+//!
+//! A catalog holds the [`Templates`] of one locale. It is a simple key/value file, one
+//! `code = template` per line, with blank lines and lines starting with `#` ignored:
+//!
+//! ```text
+//! # de.messages
+//! length = muss zwischen {min} und {max} Zeichen lang sein
+//! range = muss zwischen {min} und {max} liegen
+//! ```
+//!
+//! [`Catalogs`] renders [`ValidationError`]s in a requested locale. When the catalog of the
+//! locale has no template for a code, the less specific locale is tried, e.g. `de` for `de-AT`,
+//! then the fallback locale, and finally the default message of the error.
+
+use std::{collections::HashMap, fmt, fs, io, path::Path};
+
+use crate::error::ValidationErrors;
+use crate::message::{Templates, ValidationError};
+
+/// Extension of the catalog files loaded by [`Catalogs::load_dir`]
+pub const CATALOG_EXTENSION: &str = "messages";
+
+/// Failure to load a catalog
+#[derive(Debug)]
+pub enum CatalogError {
+    /// The catalog could not be read
+    Io(io::Error),
+    /// The line, counted from 1, is neither blank, nor a comment, nor `code = template`
+    Syntax { line: usize },
+}
+
+impl fmt::Display for CatalogError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CatalogError::Io(e) => write!(f, "cannot read catalog: {}", e),
+            CatalogError::Syntax { line } => {
+                write!(f, "line {}: expected `code = template`", line)
+            }
+        }
+    }
+}
+
+impl std::error::Error for CatalogError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            CatalogError::Io(e) => Some(e),
+            CatalogError::Syntax { .. } => None,
+        }
+    }
+}
+
+impl From<io::Error> for CatalogError {
+    fn from(value: io::Error) -> Self {
+        CatalogError::Io(value)
+    }
+}
+
+/// Parses the templates of a catalog
+pub fn parse_catalog(source: &str) -> Result<Templates, CatalogError> {
+    let mut templates = Templates::new();
+    for (i, line) in source.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        match line.split_once('=') {
+            Some((code, template)) if !code.trim().is_empty() => {
+                templates.insert(code.trim().to_string(), template.trim().to_string());
+            }
+            _ => return Err(CatalogError::Syntax { line: i + 1 }),
+        }
+    }
+    Ok(templates)
+}
+
+/// The catalogs of all the supported locales
+#[derive(Debug, Clone)]
+pub struct Catalogs {
+    catalogs: HashMap<String, Templates>,
+    fallback: String,
+}
+
+impl Catalogs {
+    /// No catalogs yet, `fallback` is the locale used when the requested one has no template
+    pub fn new(fallback: impl Into<String>) -> Self {
+        Catalogs {
+            catalogs: HashMap::new(),
+            fallback: fallback.into(),
+        }
+    }
+
+    /// Loads the catalog of every `<locale>.messages` file in `dir`
+    pub fn load_dir(
+        dir: impl AsRef<Path>,
+        fallback: impl Into<String>,
+    ) -> Result<Self, CatalogError> {
+        let mut catalogs = Catalogs::new(fallback);
+        for entry in fs::read_dir(dir)? {
+            let path = entry?.path();
+            if path.extension().and_then(|e| e.to_str()) != Some(CATALOG_EXTENSION) {
+                continue;
+            }
+            if let Some(locale) = path.file_stem().and_then(|s| s.to_str()) {
+                catalogs.load_file(locale.to_string(), &path)?;
+            }
+        }
+        Ok(catalogs)
+    }
+
+    /// Loads the catalog of `locale` from the file at `path`
+    pub fn load_file(
+        &mut self,
+        locale: impl Into<String>,
+        path: impl AsRef<Path>,
+    ) -> Result<(), CatalogError> {
+        let templates = parse_catalog(&fs::read_to_string(path)?)?;
+        self.insert(locale, templates);
+        Ok(())
+    }
+
+    /// Sets the catalog of `locale`
+    pub fn insert(&mut self, locale: impl Into<String>, templates: Templates) {
+        self.catalogs.insert(locale.into(), templates);
+    }
+
+    /// True when there is a catalog for `locale`
+    pub fn has_locale(&self, locale: &str) -> bool {
+        self.catalogs.contains_key(locale)
+    }
+
+    /// Renders `error` in `locale`, falling back as described in the [module docs][self]
+    pub fn render(&self, error: &ValidationError, locale: &str) -> String {
+        let template = self
+            .fallback_chain(locale)
+            .filter_map(|l| self.catalogs.get(l))
+            .find_map(|templates| templates.get(&error.code));
+        error.interpolate(template.unwrap_or(error.message.as_ref()))
+    }
+
+    /// Renders all the failures of a validation in `locale`
+    ///
+    /// The report keeps the names of the fields, the captured values and the number of omitted
+    /// failures, so it displays like the errors, one line per failure.
+    pub fn report(
+        &self,
+        errors: &ValidationErrors<ValidationError>,
+        locale: &str,
+    ) -> ValidationErrors<String> {
+        errors.clone().map(|e| self.render(&e, locale))
+    }
+
+    /// `locale`, its less specific locales and the fallback locale, e.g.
+    /// `de-AT-1996`, `de-AT`, `de`, `en`
+    fn fallback_chain<'a>(&'a self, locale: &'a str) -> impl Iterator<Item = &'a str> + 'a {
+        let mut current = Some(locale);
+        std::iter::from_fn(move || {
+            let next = current?;
+            current = next.rfind(['-', '_']).map(|i| &next[..i]);
+            Some(next)
+        })
+        .chain(std::iter::once(self.fallback.as_str()))
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    fn fixtures() -> Catalogs {
+        let dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/i18n");
+        Catalogs::load_dir(dir, "en").expect("fixture catalogs load")
+    }
+
+    #[test]
+    fn test_parse_catalog() {
+        let templates = parse_catalog("# comment\n\nlength = {min}..{max}\n").unwrap();
+        assert_eq!(templates.get("length"), Some("{min}..{max}"));
+        assert!(matches!(
+            parse_catalog("length = a\nwrong\n"),
+            Err(CatalogError::Syntax { line: 2 })
+        ));
+    }
+
+    #[test]
+    fn test_fallback() {
+        let catalogs = fixtures();
+        assert!(catalogs.has_locale("de"));
+        assert!(!catalogs.has_locale("README"));
+        let error = ValidationError::new("length", "default {min}")
+            .with_param("min", 3)
+            .with_param("max", 10);
+        assert_eq!(
+            catalogs.render(&error, "de"),
+            "muss zwischen 3 und 10 Zeichen lang sein"
+        );
+        assert_eq!(
+            catalogs.render(&error, "de-AT"),
+            "muss zwischen 3 und 10 Zeichen lang sein"
+        );
+        assert_eq!(
+            catalogs.render(&error, "fr"),
+            "must be 3 to 10 characters long"
+        );
+        let range = ValidationError::new("range", "default {min}").with_param("min", 1);
+        assert_eq!(catalogs.render(&range, "de"), "must be at least 1");
+        let unknown = ValidationError::new("unknown", "default {min}").with_param("min", 1);
+        assert_eq!(catalogs.render(&unknown, "de"), "default 1");
+    }
+
+    #[test]
+    fn test_report() {
+        let mut errors = ValidationErrors::new().with_limit(1);
+        let error = ValidationError::new("range", "default {min}").with_param("min", 1);
+        errors.push_with_value("age", error.clone(), Some("0".to_string()));
+        errors.push("count", error);
+        let report = fixtures().report(&errors, "de");
+        assert_eq!(report.omitted(), 1);
+        assert_eq!(
+            report.to_string(),
+            "age: must be at least 1 (got 0)\n1 more errors"
+        );
+    }
+}
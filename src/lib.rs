@@ -44,11 +44,757 @@
 //!
 //! ## Specifying your own error types
 //!
-//! By default, the error type returned by `try_from` is `Vec<String>`, which also forces the
-//! validator functions to return `Result<T, String>`.
+//! By default, the error type returned by `try_from` is
+//! [`ValidationErrors<String>`][error::ValidationErrors], which also forces the validator
+//! functions to return `Result<T, String>`.
 //!
 //! You can plug in your own error type using `#[validation_error(MyValidationError)]` attribute
 //! annotation.
+//!
+//! Validators don't have to return the error type of the struct itself: a validator returning
+//! `Result<T, FE>` can be used as long as the error type of the struct implements `From<FE>`, so
+//! e.g. the `EmailError` of a library can be converted into your own error type.
+//!
+//! With `#[validation_error(auto)]`, the derive generates the error type instead: an enum named
+//! after the struct, e.g. `AValidationError`, with one variant per validated field holding the
+//! error of that field, so matching on which field failed is checked by the compiler. The error
+//! type of a field is declared with `error_type = T` on its validator and is `String` by default.
+//! The enum implements `Debug`, `Display` and [`std::error::Error`], displaying the error of the
+//! field. With `#[validation(dedup_errors)]` it also derives `PartialEq`, `Eq` and `Hash`, which
+//! the error types of the fields then have to implement.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! #[derive(Debug, PartialEq)]
+//! struct EmailError;
+//!
+//! impl std::fmt::Display for EmailError {
+//!     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+//!         write!(f, "not an email")
+//!     }
+//! }
+//!
+//! fn is_email(s: String) -> Result<String, EmailError> {
+//!     if s.contains('@') {
+//!         Ok(s)
+//!     } else {
+//!         Err(EmailError)
+//!     }
+//! }
+//!
+//! fn is_positive(i: i32) -> Result<i32, String> {
+//!     if i > 0 {
+//!         Ok(i)
+//!     } else {
+//!         Err("must be positive".to_string())
+//!     }
+//! }
+//!
+//! #[derive(Validated, Debug)]
+//! #[validation_error(auto)]
+//! struct A {
+//!     #[validator(is_email, error_type = EmailError)]
+//!     email: String,
+//!     #[validator(is_positive)]
+//!     age: i32,
+//! }
+//!
+//! let errors = A::try_from(UnvalidatedA {
+//!     email: "abc".to_string(),
+//!     age: 1,
+//! })
+//! .unwrap_err();
+//! for error in errors {
+//!     match error {
+//!         AValidationError::Email(e) => assert_eq!(e, EmailError),
+//!         AValidationError::Age(_) => unreachable!(),
+//!     }
+//! }
+//! ```
+//!
+//! [`ValidationErrors`][error::ValidationErrors] collects the errors of all the fields that
+//! failed together with their paths, usually the names of the fields. It implements
+//! [`std::error::Error`] and displays as one line per failure. The raw errors can be taken out
+//! with [`into_vec`][error::ValidationErrors::into_vec] or by iterating over it.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! fn is_positive(i: i32) -> Result<i32, String> {
+//!     if i > 0 {
+//!         Ok(i)
+//!     } else {
+//!         Err("must be positive".to_string())
+//!     }
+//! }
+//!
+//! #[derive(Validated, Debug)]
+//! struct A {
+//!     #[validator(is_positive)]
+//!     a: i32,
+//!     #[validator(is_positive)]
+//!     b: i32,
+//! }
+//!
+//! fn load() -> Result<A, Box<dyn std::error::Error>> {
+//!     Ok(A::try_from(UnvalidatedA { a: -1, b: -2 })?)
+//! }
+//!
+//! let errors = load().unwrap_err();
+//! assert_eq!(errors.to_string(), "a: must be positive\nb: must be positive");
+//! ```
+//!
+//! ## Nested values
+//!
+//! Every failure carries its location as a [`FieldPath`][error::FieldPath], the name of the field
+//! followed by the fields and indices of nested values, which renders as a JSON Pointer
+//! (`/address/lines/2`) or in the dotted form (`address.lines.2`). Validators marked with
+//! `nested` fail with the [`ValidationErrors`][error::ValidationErrors] of the nested value, e.g.
+//! `TryFrom` of a nested derived struct, which are then reported under the name of the field.
+//! The built-in [`each`][validators::each] and [`each_nested`][validators::each_nested] validate
+//! the elements of a `Vec`, reporting the failures under their indices.
+//!
+//! ```
+//! use valibuk::validators::each;
+//! use valibuk::Validated;
+//!
+//! fn is_not_empty(s: String) -> Result<String, String> {
+//!     if s.is_empty() {
+//!         Err("empty".to_string())
+//!     } else {
+//!         Ok(s)
+//!     }
+//! }
+//!
+//! #[derive(Validated, Debug)]
+//! struct Address {
+//!     #[validator(each(is_not_empty), nested)]
+//!     lines: Vec<String>,
+//! }
+//!
+//! #[derive(Validated, Debug)]
+//! struct Person {
+//!     #[validator(Address::try_from, from = UnvalidatedAddress, nested)]
+//!     address: Address,
+//! }
+//!
+//! let errors = Person::try_from(UnvalidatedPerson {
+//!     address: UnvalidatedAddress {
+//!         lines: vec!["a".to_string(), "b".to_string(), "".to_string()],
+//!     },
+//! })
+//! .unwrap_err();
+//! let error = errors.iter().next().unwrap();
+//! assert_eq!(error.path.to_json_pointer(), "/address/lines/2");
+//! assert_eq!(errors.to_string(), "address.lines.2: empty");
+//! ```
+//!
+//! ## Limiting the errors
+//!
+//! Validating large inputs element by element can fail many times over. With
+//! `#[validation(max_errors = 100)]` at most 100 failures are recorded, the others are only
+//! counted in [`omitted`][error::ValidationErrors::omitted] and summarized as `N more errors`
+//! when displayed. `#[validation(dedup_errors)]` records identical errors of a field once, at the
+//! path of their first failure, which requires the error type to implement `Hash` and `Eq`.
+//!
+//! ## Capturing rejected values
+//!
+//! Validators with `capture_value` include a `Debug` rendering of the rejected value in the
+//! errors of the field, in [`FieldError::value`][error::FieldError::value], which is also
+//! displayed. The values of fields marked with `#[sensitive]`, e.g. passwords or tokens, are
+//! redacted, so they never appear in error reports. This includes the values captured by the
+//! fields of a `nested` value held by a sensitive field.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! fn is_positive(i: i32) -> Result<i32, String> {
+//!     if i > 0 {
+//!         Ok(i)
+//!     } else {
+//!         Err("must be positive".to_string())
+//!     }
+//! }
+//!
+//! fn is_long(s: String) -> Result<String, String> {
+//!     if s.len() >= 8 {
+//!         Ok(s)
+//!     } else {
+//!         Err("too short".to_string())
+//!     }
+//! }
+//!
+//! #[derive(Validated, Debug)]
+//! struct A {
+//!     #[validator(is_positive, capture_value)]
+//!     age: i32,
+//!     #[sensitive]
+//!     #[validator(is_long, capture_value)]
+//!     password: String,
+//! }
+//!
+//! let errors = A::try_from(UnvalidatedA { age: -1, password: "hunter2".to_string() })
+//!     .unwrap_err();
+//! assert_eq!(
+//!     errors.to_string(),
+//!     "age: must be positive (got -1)\npassword: too short (got [redacted])"
+//! );
+//! ```
+//!
+//! ## Error messages with parameters
+//!
+//! For user-facing messages, [`ValidationError`][message::ValidationError] can be used as the
+//! error type. It carries a code, e.g. `length`, and the parameters of the failure, e.g. `{min}`,
+//! `{max}` and `{actual}`, which are filled in when it is rendered with
+//! [`render`][message::ValidationError::render] using your own
+//! [`Templates`][message::Templates]. Codes without a template render the default message of the
+//! error. The built-in [validators] (`length(min, max)`, `range(min, max)`) return it, they are
+//! imported or referred to by path like any other function. Validators returning `String`
+//! errors can still be used, their message becomes the default message of an `invalid` error.
+//!
+//! ```
+//! use valibuk::message::{Templates, ValidationError};
+//! use valibuk::validators::{length, range};
+//! use valibuk::Validated;
+//!
+//! #[derive(Validated, Debug)]
+//! #[validation_error(ValidationError)]
+//! struct A {
+//!     #[validator(length(3, 10))]
+//!     name: String,
+//!     #[validator(range(18, 99))]
+//!     age: u8,
+//! }
+//!
+//! let errors = A::try_from(UnvalidatedA { name: "ab".to_string(), age: 100 }).unwrap_err();
+//! let templates = Templates::new().with("length", "{min} to {max} characters please");
+//! let messages: Vec<_> = errors.iter().map(|e| e.error.render(&templates)).collect();
+//! assert_eq!(
+//!     messages,
+//!     vec!["3 to 10 characters please", "must be between 18 and 99, got 100"]
+//! );
+//! ```
+//!
+//! ## Localization
+//!
+//! The templates of each locale can be loaded from message catalogs, simple `code = template`
+//! files, with [`i18n::Catalogs`]. It renders the errors in a requested locale, falling back to
+//! less specific locales (`de` for `de-AT`), then to a fallback locale, and finally to the
+//! default message of the error.
+//!
+//! ```no_run
+//! use valibuk::i18n::Catalogs;
+//! use valibuk::message::ValidationError;
+//! use valibuk::Validated;
+//!
+//! #[derive(Validated, Debug)]
+//! #[validation_error(ValidationError)]
+//! struct A {
+//!     #[validator(valibuk::validators::length(3, 10))]
+//!     name: String,
+//! }
+//!
+//! // loads `en.messages`, `de.messages`, ...
+//! let catalogs = Catalogs::load_dir("locales", "en").unwrap();
+//! let errors = A::try_from(UnvalidatedA { name: "ab".to_string() }).unwrap_err();
+//! println!("{}", catalogs.report(&errors, "de-AT"));
+//! ```
+//!
+//! ## Problem details
+//!
+//! With the `problem-details` feature, [`ValidationErrors`][error::ValidationErrors] converts
+//! into an RFC 7807 `application/problem+json` document with `problem::ProblemDetails`, listing
+//! the failures in its `invalid-params` array, so HTTP handlers can return it directly, e.g.
+//! `ProblemDetails::from(errors).with_status(422).to_json()`.
+//!
+//! ## Diagnostics
+//!
+//! With the `miette` feature, the errors of a struct deserialized from a config file can be
+//! reported as a `diagnostic::ValidationReport`, implementing `miette::Diagnostic` with labels at
+//! the offending values in the original text. The byte spans of the values are given by their
+//! paths in `diagnostic::Spans`, found in JSON texts with `Spans::from_json`, and with the `toml`
+//! feature in TOML texts with `Spans::from_toml`, backed by `toml_edit`. For other formats they
+//! are inserted one by one with `Spans::insert`.
+//!
+//! ## Converting back to unvalidated
+//!
+//! The derive also generates `From<A> for UnvalidatedA`, so a valid instance can be edited and
+//! validated again.
+//!
+//! Validators can change the type of the field with `#[validator(parse, from = U)]`, where
+//! `parse` is a `fn(U) -> Result<T, E>` and `U` is the type of the field in the unvalidated
+//! struct. Such fields need an `into = f` mapping, with `f` being a `fn(T) -> U`, for the
+//! `From` conversion to be generated.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! struct Email(String);
+//!
+//! fn parse_email(s: String) -> Result<Email, String> {
+//!     if s.contains('@') {
+//!         Ok(Email(s))
+//!     } else {
+//!         Err("not an email".to_string())
+//!     }
+//! }
+//!
+//! #[derive(Validated)]
+//! struct A {
+//!     #[validator(parse_email, from = String, into = |e: Email| e.0)]
+//!     email: Email,
+//! }
+//!
+//! let a = A::try_from(UnvalidatedA { email: "a@example.com".to_string() }).unwrap();
+//! let mut unvalidated = UnvalidatedA::from(a);
+//! unvalidated.email.push_str(".org");
+//! let a = A::try_from(unvalidated).unwrap();
+//! assert_eq!(a.email.0, "a@example.com.org");
+//! ```
+//!
+//! ## Validation context
+//!
+//! Validators that need runtime data (allowed tenants, current time, feature flags) can receive
+//! a context. Declare its type with `#[validation(context = MyCtx)]` and mark such validators
+//! with `context`, they then have the form of `fn(T, &MyCtx) -> Result<T, E>`. Context-free
+//! validators keep working in the same struct. Instead of `TryFrom`, the derive generates
+//! `A::validate_with(unvalidated, &ctx)`.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! struct Limits {
+//!     max: i32,
+//! }
+//!
+//! fn is_below_max(i: i32, limits: &Limits) -> Result<i32, String> {
+//!     if i <= limits.max {
+//!         Ok(i)
+//!     } else {
+//!         Err("too large".to_string())
+//!     }
+//! }
+//!
+//! #[derive(Validated)]
+//! #[validation(context = Limits)]
+//! struct A {
+//!     #[validator(is_below_max, context)]
+//!     a: i32,
+//! }
+//!
+//! let limits = Limits { max: 10 };
+//! assert!(A::validate_with(UnvalidatedA { a: 5 }, &limits).is_ok());
+//! assert!(A::validate_with(UnvalidatedA { a: 50 }, &limits).is_err());
+//! ```
+//!
+//! ## Async validators
+//!
+//! Validators doing I/O (e.g. checking that a username is not taken) can be async. Mark them with
+//! `#[validator(async check_unique)]`, where `check_unique` is an `async fn(T) -> Result<T, E>`.
+//! The derive then generates `A::validate_async(unvalidated).await` instead of `TryFrom`. The
+//! async validators of all fields are awaited concurrently and their errors are collected the
+//! same way as in the sync path. Async validators can require a context too, in which case
+//! `validate_async` takes it as its second argument.
+//!
+//! ## Sanitizers
+//!
+//! Fields can be normalized before they are validated using `#[sanitizer(...)]`. It takes a list
+//! of `fn(T) -> T` functions which are applied in order, so the validated struct holds the
+//! normalized data. The built-in [sanitizers] (`trim`, `lowercase`, `uppercase`,
+//! `collapse_whitespace`) are plain functions, which are imported or referred to by path like
+//! any other. The attribute is not called `sanitize`, as that name is taken by a built-in
+//! attribute of the compiler.
+//!
+//! ```
+//! use valibuk::sanitizers::{lowercase, trim};
+//! use valibuk::Validated;
+//!
+//! fn is_email(s: String) -> Result<String, String> {
+//!     if s.contains('@') {
+//!         Ok(s)
+//!     } else {
+//!         Err("not an email".to_string())
+//!     }
+//! }
+//!
+//! #[derive(Validated)]
+//! struct A {
+//!     #[sanitizer(trim, lowercase)]
+//!     #[validator(is_email)]
+//!     email: String,
+//! }
+//!
+//! let a = A::try_from(UnvalidatedA { email: " Bob@Example.com ".to_string() }).unwrap();
+//! assert_eq!(a.email, "bob@example.com");
+//! ```
+//!
+//! ## Default values
+//!
+//! To model partially-specified input, fields can have a default using `#[default = literal]`,
+//! `#[default(expr)]`, or `#[default]` for `Default::default()`. String literals are converted
+//! with `Into`, so they can be used for `String` fields. The field then becomes an `Option<T>` in the
+//! unvalidated struct and the default is filled in before the field is sanitized and
+//! validated, so the validated struct stays fully populated.
+//!
+//! `#[derive(Default)]` claims the `#[default]` attribute as well, so structs deriving both
+//! `Validated` and `Default` fail to compile with it. Such structs use the same forms of
+//! `#[validation_default]` instead, e.g. `#[validation_default = 8080]`.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! #[derive(Validated)]
+//! struct Config {
+//!     #[default = "localhost"]
+//!     host: String,
+//!     #[default = 8080]
+//!     port: u16,
+//!     #[default]
+//!     verbose: bool,
+//! }
+//!
+//! let config = Config::try_from(UnvalidatedConfig {
+//!     host: None,
+//!     port: None,
+//!     verbose: None,
+//! })
+//! .unwrap();
+//! assert_eq!(config.host, "localhost");
+//! assert_eq!(config.port, 8080);
+//! assert!(!config.verbose);
+//! ```
+//!
+//! ## Builder
+//!
+//! Constructing the unvalidated struct literally breaks every call site when a field is added.
+//! With `#[validation(builder)]` the derive generates `UnvalidatedA::builder()` with a setter for
+//! each field. The builder ends in `build()`, yielding the unvalidated struct, or in `validate()`,
+//! running the same validation as `TryFrom`. Both fail with a [`builder::MissingField`] when a
+//! field without a default was not set. The setters take anything converting into the type of
+//! the field with `Into`, e.g. a `&str` for a `String`, so integer literals for fields other than
+//! `i32` need a suffix, e.g. `port(8080u16)`.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! #[derive(Validated)]
+//! #[validation(builder)]
+//! struct A {
+//!     a: i32,
+//!     b: String,
+//! }
+//!
+//! let a = UnvalidatedA::builder().a(1).b("x").validate().unwrap();
+//! assert_eq!(a.b, "x");
+//! assert!(UnvalidatedA::builder().a(1).validate().is_err());
+//! ```
+//!
+//! With `#[validation(builder = typestate)]` the builder tracks in its type which of the fields
+//! without a default are set. `build()` and `validate()` only exist once all of them are, so
+//! forgetting a field is a compile error instead of a runtime one.
+//!
+//! ```compile_fail
+//! use valibuk::Validated;
+//!
+//! #[derive(Validated)]
+//! #[validation(builder = typestate)]
+//! struct A {
+//!     a: i32,
+//!     b: String,
+//! }
+//!
+//! // `b` is not set, so there is no `build()`
+//! let a = UnvalidatedA::builder().a(1).build();
+//! ```
+//!
+//! ## Setters
+//!
+//! To change a field of a valid instance without going back to the unvalidated struct, use
+//! `#[validation(setters)]`. It generates `try_set_<field>(value)` methods on the validated
+//! struct, which sanitize and validate only the new value and leave the instance untouched
+//! when it is rejected. They take the context and are async when the validator of the field
+//! requires it.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! fn is_positive(i: i32) -> Result<i32, String> {
+//!     if i > 0 {
+//!         Ok(i)
+//!     } else {
+//!         Err("wrong".to_string())
+//!     }
+//! }
+//!
+//! #[derive(Validated)]
+//! #[validation(setters)]
+//! struct A {
+//!     #[validator(is_positive)]
+//!     a: i32,
+//! }
+//!
+//! let mut a = A::try_from(UnvalidatedA { a: 1 }).unwrap();
+//! assert!(a.try_set_a(2).is_ok());
+//! assert_eq!(a.try_set_a(-1), Err("wrong".to_string()));
+//! assert_eq!(a.a, 2);
+//! ```
+//!
+//! ## Patches
+//!
+//! For partial updates, `#[validation(patch)]` generates a `PatchA` struct with every field
+//! optional, and `a.apply_patch(patch)`. It sanitizes and validates only the fields present in
+//! the patch and keeps the others as they are. `PatchA` implements `Default`, so a patch can be
+//! written as `PatchA { a: Some(1), ..Default::default() }`.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! fn is_positive(i: i32) -> Result<i32, String> {
+//!     if i > 0 {
+//!         Ok(i)
+//!     } else {
+//!         Err("wrong".to_string())
+//!     }
+//! }
+//!
+//! #[derive(Validated)]
+//! #[validation(patch)]
+//! struct A {
+//!     #[validator(is_positive)]
+//!     a: i32,
+//!     b: String,
+//! }
+//!
+//! let a = A::try_from(UnvalidatedA { a: 1, b: "b".to_string() }).unwrap();
+//! let a = a.apply_patch(PatchA { a: Some(2), ..Default::default() }).unwrap();
+//! assert_eq!(a.a, 2);
+//! assert_eq!(a.b, "b");
+//! assert!(a.apply_patch(PatchA { a: Some(-1), b: None }).is_err());
+//! ```
+//!
+//! ## Validation groups
+//!
+//! When the same struct needs different rules in different flows, validators can be assigned to
+//! [groups] with `#[validator(is_present, groups(Update))]`. Such validators only run in
+//! `A::validate_for::<Update>(unvalidated)`, after the validators without groups, which always
+//! run. `TryFrom` and the other entry points ignore the validators with groups. `Create` and
+//! `Update` are provided in [groups], any type implementing [`groups::Group`] can be a group.
+//! Groups are listed by their types, so a misspelled group fails to compile.
+//!
+//! ```
+//! use valibuk::groups::{Create, Update};
+//! use valibuk::Validated;
+//!
+//! fn is_absent(id: Option<u64>) -> Result<Option<u64>, String> {
+//!     match id {
+//!         None => Ok(id),
+//!         Some(_) => Err("id must not be set".to_string()),
+//!     }
+//! }
+//!
+//! fn is_present(id: Option<u64>) -> Result<Option<u64>, String> {
+//!     match id {
+//!         Some(_) => Ok(id),
+//!         None => Err("id is required".to_string()),
+//!     }
+//! }
+//!
+//! #[derive(Validated)]
+//! struct User {
+//!     #[validator(is_absent, groups(Create))]
+//!     #[validator(is_present, groups(Update))]
+//!     id: Option<u64>,
+//!     name: String,
+//! }
+//!
+//! let new = || UnvalidatedUser { id: None, name: "a".to_string() };
+//! assert!(User::validate_for::<Create>(new()).is_ok());
+//! assert!(User::validate_for::<Update>(new()).is_err());
+//! ```
+//!
+//! ## Checking without moving
+//!
+//! Validators prefixed with `ref` take the value by reference, i.e. `fn(&T) -> Result<(), E>`, so
+//! the value does not have to be moved through them. Besides `TryFrom`, they are run by the
+//! generated `UnvalidatedA::check(&self)`, which tells whether the unvalidated struct is valid
+//! without consuming it, e.g. for live feedback on a form. `check` skips all the other
+//! validators, and as sanitizers need to own the value, it sees the values before they are
+//! sanitized.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! fn is_not_empty(s: &String) -> Result<(), String> {
+//!     if s.is_empty() {
+//!         Err("empty".to_string())
+//!     } else {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[derive(Validated)]
+//! struct A {
+//!     #[validator(ref is_not_empty)]
+//!     name: String,
+//! }
+//!
+//! let unvalidated = UnvalidatedA { name: String::new() };
+//! assert_eq!(unvalidated.check().unwrap_err().into_vec(), vec!["empty".to_string()]);
+//! assert!(A::try_from(unvalidated).is_err());
+//! ```
+//!
+//! ## Conditional validators
+//!
+//! A validator can be made to depend on other fields with `when`, taking a
+//! `fn(&UnvalidatedA) -> bool`. The condition is evaluated against the unvalidated input and
+//! when it does not hold, the value is taken as is. Setters and patches have no unvalidated
+//! input to evaluate it against, so `when` fails to compile with `setters` or `patch`.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! fn valid_vat(vat_id: String) -> Result<String, String> {
+//!     if vat_id.starts_with("DE") && vat_id.len() == 11 {
+//!         Ok(vat_id)
+//!     } else {
+//!         Err("invalid VAT ID".to_string())
+//!     }
+//! }
+//!
+//! #[derive(Validated)]
+//! struct Company {
+//!     country: String,
+//!     #[validator(valid_vat, when = |u: &UnvalidatedCompany| u.country == "DE")]
+//!     vat_id: String,
+//! }
+//!
+//! let company = |country: &str| UnvalidatedCompany {
+//!     country: country.to_string(),
+//!     vat_id: String::new(),
+//! };
+//! assert!(Company::try_from(company("US")).is_ok());
+//! assert!(Company::try_from(company("DE")).is_err());
+//! ```
+//!
+//! ## Warnings
+//!
+//! Checks that should be reported without rejecting the input can be marked with
+//! `#[validator(weak_password, severity = warn)]`. Such validators take the value by reference,
+//! i.e. `fn(&T) -> Result<(), E>`, and run once all the other validators succeeded.
+//! `A::validate_with_warnings(unvalidated)` then yields the value together with the warnings as
+//! [`Validated<A, ValidationErrors<E>>`][struct@Validated], while errors still fail it.
+//! `TryFrom` ignores them.
+//!
+//! ```
+//! use valibuk::Validated;
+//!
+//! fn weak_password(p: &String) -> Result<(), String> {
+//!     if p.len() < 12 {
+//!         Err("password is weak".to_string())
+//!     } else {
+//!         Ok(())
+//!     }
+//! }
+//!
+//! #[derive(Validated)]
+//! struct Account {
+//!     #[validator(weak_password, severity = warn)]
+//!     password: String,
+//! }
+//!
+//! let account = Account::validate_with_warnings(UnvalidatedAccount {
+//!     password: "hunter2".to_string(),
+//! })
+//! .unwrap();
+//! assert_eq!(account.warnings.to_string(), "password: password is weak");
+//! assert_eq!(account.password, "hunter2");
+//! ```
+//!
+//! ## Partial results
+//!
+//! To find out which fields of a failing input were fine, use `#[validation(partial)]`. It
+//! generates `A::validate_partial(unvalidated)`, yielding a [`partial::PartialResult`]. When
+//! some fields fail, it holds an `APartial` struct, with each field that validated successfully
+//! set and the others `None`, together with the errors.
+//!
+//! ```
+//! use valibuk::partial::PartialResult;
+//! use valibuk::Validated;
+//!
+//! fn is_positive(i: i32) -> Result<i32, String> {
+//!     if i > 0 {
+//!         Ok(i)
+//!     } else {
+//!         Err("wrong".to_string())
+//!     }
+//! }
+//!
+//! #[derive(Validated)]
+//! #[validation(partial)]
+//! struct A {
+//!     #[validator(is_positive)]
+//!     a: i32,
+//!     #[validator(is_positive)]
+//!     b: i32,
+//! }
+//!
+//! match A::validate_partial(UnvalidatedA { a: 1, b: -1 }) {
+//!     PartialResult::Invalid { fields, errors } => {
+//!         assert_eq!(fields.a, Some(1));
+//!         assert_eq!(fields.b, None);
+//!         assert_eq!(errors.into_vec(), vec!["wrong".to_string()]);
+//!     }
+//!     PartialResult::Valid(_) => unreachable!(),
+//! }
+//! ```
+//!
+//! ## Sealed structs
+//!
+//! Nothing stops code that can see the fields of the validated struct from constructing it
+//! directly, bypassing validation. With `#[validation(sealed)]` the derive rejects structs with
+//! non-private fields and generates a read-only getter for each field instead. Outside of the
+//! module it is declared in, the only way of obtaining an instance is then to validate one, so
+//! sealed structs are best kept in a module of their own.
+//!
+//! ```
+//! mod user {
+//!     use valibuk::Validated;
+//!
+//!     fn is_positive(i: i32) -> Result<i32, String> {
+//!         if i > 0 {
+//!             Ok(i)
+//!         } else {
+//!             Err("wrong".to_string())
+//!         }
+//!     }
+//!
+//!     #[derive(Validated)]
+//!     #[validation(sealed)]
+//!     pub struct User {
+//!         #[validator(is_positive)]
+//!         age: i32,
+//!     }
+//! }
+//!
+//! let user = user::User::try_from(user::UnvalidatedUser { age: 30 }).unwrap();
+//! assert_eq!(*user.age(), 30);
+//! ```
 extern crate valibuk_derive;
 
+pub mod builder;
+#[cfg(feature = "miette")]
+pub mod diagnostic;
+pub mod error;
+pub mod groups;
+pub mod i18n;
+pub mod message;
+pub mod partial;
+#[cfg(feature = "problem-details")]
+pub mod problem;
+pub mod sanitizers;
+pub mod validators;
+pub mod warnings;
+
 pub use valibuk_derive::Validated;
+pub use warnings::Validated;
@@ -0,0 +1,155 @@
+//! Validation errors with a code and parameters, rendered through message templates
+//!
+//! [`ValidationError`] can be used as `#[validation_error(ValidationError)]`. Instead of a
+//! finished message it carries a code, e.g. `length`, and the parameters of the failure, e.g.
+//! `{min}`, `{max}` and `{actual}`, so that user-facing messages can be customized per code with
+//! [`Templates`]. The [built-in validators][crate::validators] return it.
+
+use std::{borrow::Cow, collections::HashMap, fmt};
+
+/// Failure of a validator, identified by its code
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub struct ValidationError {
+    /// Identifies the kind of failure, e.g. `length`, and selects its template
+    pub code: Cow<'static, str>,
+    /// Values filled in for the `{name}` placeholders of the template, in order of insertion
+    pub params: Vec<(Cow<'static, str>, String)>,
+    /// Default template, used when [`Templates`] has none for the code
+    pub message: Cow<'static, str>,
+}
+
+impl ValidationError {
+    /// Failure with the given code and default template, without parameters
+    pub fn new(code: impl Into<Cow<'static, str>>, message: impl Into<Cow<'static, str>>) -> Self {
+        ValidationError {
+            code: code.into(),
+            params: Vec::new(),
+            message: message.into(),
+        }
+    }
+
+    /// Adds the parameter `name`, filled in for `{name}` in the template
+    pub fn with_param(
+        mut self,
+        name: impl Into<Cow<'static, str>>,
+        value: impl fmt::Display,
+    ) -> Self {
+        self.params.push((name.into(), value.to_string()));
+        self
+    }
+
+    /// Value of the parameter `name`
+    pub fn param(&self, name: &str) -> Option<&str> {
+        self.params
+            .iter()
+            .find(|(n, _)| n == name)
+            .map(|(_, v)| v.as_str())
+    }
+
+    /// Renders the template for the code of the error, or its default message when there is none
+    pub fn render(&self, templates: &Templates) -> String {
+        let template = templates.get(&self.code).unwrap_or(self.message.as_ref());
+        self.interpolate(template)
+    }
+
+    /// Replaces the `{name}` placeholders in `template` with the parameters of the error
+    ///
+    /// Placeholders of unknown parameters are kept as they are.
+    pub fn interpolate(&self, template: &str) -> String {
+        let mut rendered = String::with_capacity(template.len());
+        let mut rest = template;
+        while let Some(start) = rest.find('{') {
+            rendered.push_str(&rest[..start]);
+            let placeholder = &rest[start..];
+            let value = placeholder
+                .find('}')
+                .and_then(|end| Some((end, self.param(&placeholder[1..end])?)));
+            match value {
+                Some((end, value)) => {
+                    rendered.push_str(value);
+                    rest = &placeholder[end + 1..];
+                }
+                None => {
+                    rendered.push('{');
+                    rest = &placeholder[1..];
+                }
+            }
+        }
+        rendered.push_str(rest);
+        rendered
+    }
+}
+
+impl fmt::Display for ValidationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.interpolate(&self.message))
+    }
+}
+
+impl std::error::Error for ValidationError {}
+
+/// Validators returning `Result<T, String>` can be used with [`ValidationError`] as well, their
+/// message becomes the default message of an `invalid` error
+impl From<String> for ValidationError {
+    fn from(message: String) -> Self {
+        ValidationError::new("invalid", message)
+    }
+}
+
+/// Message templates by error code
+///
+/// Templates refer to the parameters of the error as `{name}`, e.g.
+/// `"between {min} and {max} characters please"`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Templates {
+    templates: HashMap<Cow<'static, str>, Cow<'static, str>>,
+}
+
+impl Templates {
+    /// No templates, errors render their default messages
+    pub fn new() -> Self {
+        Templates::default()
+    }
+
+    /// Sets the template for `code`
+    pub fn insert(
+        &mut self,
+        code: impl Into<Cow<'static, str>>,
+        template: impl Into<Cow<'static, str>>,
+    ) {
+        self.templates.insert(code.into(), template.into());
+    }
+
+    /// Sets the template for `code`, for chaining
+    pub fn with(
+        mut self,
+        code: impl Into<Cow<'static, str>>,
+        template: impl Into<Cow<'static, str>>,
+    ) -> Self {
+        self.insert(code, template);
+        self
+    }
+
+    /// The template for `code`
+    pub fn get(&self, code: &str) -> Option<&str> {
+        self.templates.get(code).map(|t| t.as_ref())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_render() {
+        let error = ValidationError::new("length", "must be {min} to {max} long, not {actual}")
+            .with_param("min", 3)
+            .with_param("max", 10)
+            .with_param("actual", 2);
+        assert_eq!(error.to_string(), "must be 3 to 10 long, not 2");
+        let templates = Templates::new().with("length", "{min}..{max} please, {unknown} {");
+        assert_eq!(error.render(&templates), "3..10 please, {unknown} {");
+        let other = Templates::new().with("range", "{min}..{max}");
+        assert_eq!(error.render(&other), "must be 3 to 10 long, not 2");
+    }
+}
@@ -0,0 +1,41 @@
+//! Support types of `validate_partial`, generated with `#[validation(partial)]`
+
+use crate::error::ValidationErrors;
+
+/// A validated struct with a partial counterpart, holding each field that validated successfully
+///
+/// Implemented by the derive for structs with `#[validation(partial)]`
+pub trait Partial {
+    /// The partial struct, `APartial` for struct `A`
+    type Fields;
+    /// The error type of the validators
+    type Error;
+}
+
+/// Result of `validate_partial`
+pub enum PartialResult<T: Partial> {
+    /// All the fields are valid
+    Valid(T),
+    /// Some of the fields are not valid
+    Invalid {
+        /// The fields that validated successfully, the others are `None`
+        fields: T::Fields,
+        /// Errors of the fields that failed to validate
+        errors: ValidationErrors<T::Error>,
+    },
+}
+
+impl<T: Partial> PartialResult<T> {
+    /// True when all the fields are valid
+    pub fn is_valid(&self) -> bool {
+        matches!(self, PartialResult::Valid(_))
+    }
+
+    /// Drops the fields that validated successfully when others failed
+    pub fn into_result(self) -> Result<T, ValidationErrors<T::Error>> {
+        match self {
+            PartialResult::Valid(value) => Ok(value),
+            PartialResult::Invalid { errors, .. } => Err(errors),
+        }
+    }
+}
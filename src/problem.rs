@@ -0,0 +1,215 @@
+//! RFC 7807 problem details for validation errors
+//!
+//! Available with the `problem-details` feature. [`ProblemDetails`] turns the errors of a
+//! validation into an `application/problem+json` document, listing every failure in its
+//! `invalid-params` array with the name of the field, the message of the error and the captured
+//! value, if any. Failures left out of the errors are counted in `omitted-params`:
+//!
+//! ```json
+//! {
+//!   "type": "about:blank",
+//!   "title": "Your request parameters didn't validate.",
+//!   "status": 400,
+//!   "invalid-params": [{ "name": "age", "reason": "must be positive" }]
+//! }
+//! ```
+
+use std::fmt;
+
+use crate::error::ValidationErrors;
+
+/// A failure listed in `invalid-params`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct InvalidParam {
+    /// Name of the field that failed to validate
+    pub name: String,
+    /// Message of the error
+    pub reason: String,
+    /// Rendering of the rejected value, for validators with `capture_value`
+    pub value: Option<String>,
+}
+
+/// Problem details document describing failed validation
+///
+/// ```
+/// use valibuk::problem::ProblemDetails;
+/// use valibuk::Validated;
+///
+/// fn is_positive(i: i32) -> Result<i32, String> {
+///     if i > 0 {
+///         Ok(i)
+///     } else {
+///         Err("must be positive".to_string())
+///     }
+/// }
+///
+/// #[derive(Validated, Debug)]
+/// struct A {
+///     #[validator(is_positive)]
+///     age: i32,
+/// }
+///
+/// let errors = A::try_from(UnvalidatedA { age: -1 }).unwrap_err();
+/// let body = ProblemDetails::from(errors).with_status(422).to_json();
+/// assert_eq!(body["invalid-params"][0]["reason"], "must be positive");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ProblemDetails {
+    /// URI identifying the problem type, `about:blank` by default
+    pub type_uri: String,
+    /// Short summary of the problem type
+    pub title: String,
+    /// HTTP status code, 400 by default
+    pub status: u16,
+    /// Explanation specific to this occurrence of the problem
+    pub detail: Option<String>,
+    /// The failures of the validation
+    pub invalid_params: Vec<InvalidParam>,
+    /// Number of failures left out of `invalid_params`, over the limit or duplicates
+    pub omitted: usize,
+}
+
+impl ProblemDetails {
+    /// Media type of the JSON document
+    pub const CONTENT_TYPE: &'static str = "application/problem+json";
+
+    /// Problem details listing the failures of `errors`, displayed as their reasons
+    pub fn from_errors<E: fmt::Display>(errors: &ValidationErrors<E>) -> Self {
+        ProblemDetails {
+            type_uri: "about:blank".to_string(),
+            title: "Your request parameters didn't validate.".to_string(),
+            status: 400,
+            detail: None,
+            invalid_params: errors
+                .iter()
+                .map(|e| InvalidParam {
+                    name: e.path.to_dotted(),
+                    reason: e.error.to_string(),
+                    value: e.value.clone(),
+                })
+                .collect(),
+            omitted: errors.omitted(),
+        }
+    }
+
+    /// Sets the URI identifying the problem type
+    pub fn with_type(mut self, type_uri: impl Into<String>) -> Self {
+        self.type_uri = type_uri.into();
+        self
+    }
+
+    /// Sets the summary of the problem type
+    pub fn with_title(mut self, title: impl Into<String>) -> Self {
+        self.title = title.into();
+        self
+    }
+
+    /// Sets the HTTP status code, e.g. 422
+    pub fn with_status(mut self, status: u16) -> Self {
+        self.status = status;
+        self
+    }
+
+    /// Sets the explanation of this occurrence of the problem
+    pub fn with_detail(mut self, detail: impl Into<String>) -> Self {
+        self.detail = Some(detail.into());
+        self
+    }
+
+    /// The problem details JSON document
+    ///
+    /// `value` of the failures and `omitted-params` are left out when there is none.
+    pub fn to_json(&self) -> serde_json::Value {
+        let mut document = serde_json::json!({
+            "type": self.type_uri,
+            "title": self.title,
+            "status": self.status,
+            "invalid-params": self
+                .invalid_params
+                .iter()
+                .map(|p| {
+                    let mut param = serde_json::json!({ "name": p.name, "reason": p.reason });
+                    if let Some(value) = &p.value {
+                        param["value"] = serde_json::Value::String(value.clone());
+                    }
+                    param
+                })
+                .collect::<Vec<_>>(),
+        });
+        if let Some(detail) = &self.detail {
+            document["detail"] = serde_json::Value::String(detail.clone());
+        }
+        if self.omitted > 0 {
+            document["omitted-params"] = self.omitted.into();
+        }
+        document
+    }
+}
+
+impl<E: fmt::Display> From<ValidationErrors<E>> for ProblemDetails {
+    fn from(errors: ValidationErrors<E>) -> Self {
+        ProblemDetails::from_errors(&errors)
+    }
+}
+
+impl From<ProblemDetails> for serde_json::Value {
+    fn from(problem: ProblemDetails) -> Self {
+        problem.to_json()
+    }
+}
+
+/// Displays as the JSON document
+impl fmt::Display for ProblemDetails {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.to_json())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_to_json() {
+        let mut errors = ValidationErrors::new();
+        errors.push("age", "must be positive".to_string());
+        let problem = ProblemDetails::from(errors)
+            .with_status(422)
+            .with_detail("the age is invalid");
+        assert_eq!(
+            problem.to_json(),
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Your request parameters didn't validate.",
+                "status": 422,
+                "detail": "the age is invalid",
+                "invalid-params": [{ "name": "age", "reason": "must be positive" }],
+            })
+        );
+    }
+
+    #[test]
+    fn test_value_and_omitted() {
+        let mut errors = ValidationErrors::new().with_limit(1);
+        errors.push_with_value(
+            "age",
+            "must be positive".to_string(),
+            Some("-1".to_string()),
+        );
+        errors.push("name", "missing".to_string());
+        let problem = ProblemDetails::from(errors);
+        assert_eq!(problem.omitted, 1);
+        assert_eq!(
+            problem.to_json(),
+            serde_json::json!({
+                "type": "about:blank",
+                "title": "Your request parameters didn't validate.",
+                "status": 400,
+                "invalid-params": [
+                    { "name": "age", "reason": "must be positive", "value": "-1" }
+                ],
+                "omitted-params": 1,
+            })
+        );
+    }
+}
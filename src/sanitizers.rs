@@ -0,0 +1,67 @@
+//! Built-in sanitizers for `#[sanitizer(...)]`
+//!
+//! Sanitizers normalize the value of a field before its validator runs. They have the form of
+//! `fn(T) -> T`, any such function can be used in `#[sanitizer(...)]` next to the ones defined
+//! here. Like any other function, these are referred to by path or brought into scope with
+//! `use valibuk::sanitizers::{trim, lowercase}`.
+
+/// Removes leading and trailing whitespace
+pub fn trim(s: String) -> String {
+    let trimmed = s.trim();
+    if trimmed.len() == s.len() {
+        s
+    } else {
+        trimmed.to_string()
+    }
+}
+
+/// Converts the string to lowercase
+pub fn lowercase(s: String) -> String {
+    s.to_lowercase()
+}
+
+/// Converts the string to uppercase
+pub fn uppercase(s: String) -> String {
+    s.to_uppercase()
+}
+
+/// Replaces every run of whitespace with a single space
+pub fn collapse_whitespace(s: String) -> String {
+    let mut collapsed = String::with_capacity(s.len());
+    let mut previous_whitespace = false;
+    for c in s.chars() {
+        if c.is_whitespace() {
+            if !previous_whitespace {
+                collapsed.push(' ');
+            }
+            previous_whitespace = true;
+        } else {
+            collapsed.push(c);
+            previous_whitespace = false;
+        }
+    }
+    collapsed
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_trim() {
+        assert_eq!(trim("  a b  ".to_string()), "a b");
+        assert_eq!(trim("a".to_string()), "a");
+    }
+
+    #[test]
+    fn test_case() {
+        assert_eq!(lowercase("AbC".to_string()), "abc");
+        assert_eq!(uppercase("AbC".to_string()), "ABC");
+    }
+
+    #[test]
+    fn test_collapse_whitespace() {
+        assert_eq!(collapse_whitespace("a \t b\n\nc".to_string()), "a b c");
+        assert_eq!(collapse_whitespace(" a ".to_string()), " a ");
+    }
+}
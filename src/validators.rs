@@ -0,0 +1,128 @@
+//! Built-in validators for `#[validator(...)]`
+//!
+//! The validators here are parametrized, so they are constructed in the attribute, e.g.
+//! `#[validator(length(3, 10))]`, once imported or referred to by path. [`length`] and [`range`]
+//! fail with a [`ValidationError`] carrying their parameters together with the actual value, so
+//! they can be used with `#[validation_error(ValidationError)]`, or with any error type
+//! implementing `From<ValidationError>`. [`each`] and [`each_nested`] validate the elements of a
+//! collection and report the failures at their indices, so they are used with `nested`.
+
+use std::fmt;
+
+use crate::error::ValidationErrors;
+use crate::message::ValidationError;
+
+/// Accepts strings of `min` to `max` characters, inclusive
+///
+/// Fails with code `length` and parameters `min`, `max` and `actual`.
+pub fn length<T: AsRef<str>>(min: usize, max: usize) -> impl Fn(T) -> Result<T, ValidationError> {
+    move |value| {
+        let actual = value.as_ref().chars().count();
+        if (min..=max).contains(&actual) {
+            Ok(value)
+        } else {
+            Err(ValidationError::new(
+                "length",
+                "must be between {min} and {max} characters long, got {actual}",
+            )
+            .with_param("min", min)
+            .with_param("max", max)
+            .with_param("actual", actual))
+        }
+    }
+}
+
+/// Accepts values from `min` to `max`, inclusive
+///
+/// Fails with code `range` and parameters `min`, `max` and `actual`.
+pub fn range<T: PartialOrd + fmt::Display>(
+    min: T,
+    max: T,
+) -> impl Fn(T) -> Result<T, ValidationError> {
+    move |value| {
+        if min <= value && value <= max {
+            Ok(value)
+        } else {
+            Err(
+                ValidationError::new("range", "must be between {min} and {max}, got {actual}")
+                    .with_param("min", &min)
+                    .with_param("max", &max)
+                    .with_param("actual", &value),
+            )
+        }
+    }
+}
+
+/// Validates every element with `validator`
+///
+/// The failures are reported at the index of their element, e.g.
+/// `#[validator(each(is_not_empty), nested)]`.
+pub fn each<T, U, E>(
+    validator: impl Fn(T) -> Result<U, E>,
+) -> impl Fn(Vec<T>) -> Result<Vec<U>, ValidationErrors<E>> {
+    each_nested(move |value| {
+        validator(value).map_err(|e| {
+            let mut errors = ValidationErrors::new();
+            errors.push_at(Default::default(), e);
+            errors
+        })
+    })
+}
+
+/// Validates every element with `validator`, which fails with the errors of the element, e.g.
+/// `Line::try_from` for a derived `Line`
+///
+/// The failures are reported under the index of their element, e.g.
+/// `#[validator(each_nested(Line::try_from), from = Vec<UnvalidatedLine>, nested)]`.
+pub fn each_nested<T, U, E>(
+    validator: impl Fn(T) -> Result<U, ValidationErrors<E>>,
+) -> impl Fn(Vec<T>) -> Result<Vec<U>, ValidationErrors<E>> {
+    move |values| {
+        let mut valid = Vec::with_capacity(values.len());
+        let mut errors = ValidationErrors::new();
+        for (i, value) in values.into_iter().enumerate() {
+            match validator(value) {
+                Ok(value) => valid.push(value),
+                Err(e) => errors.nest(i, e),
+            }
+        }
+        if errors.is_empty() {
+            Ok(valid)
+        } else {
+            Err(errors)
+        }
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    #[test]
+    fn test_length() {
+        assert_eq!(length(1, 3)("abc".to_string()), Ok("abc".to_string()));
+        let error = length(1, 2)("čau").unwrap_err();
+        assert_eq!(error.code, "length");
+        assert_eq!(error.param("actual"), Some("3"));
+        assert_eq!(
+            error.to_string(),
+            "must be between 1 and 2 characters long, got 3"
+        );
+    }
+
+    #[test]
+    fn test_each() {
+        let errors = each(length(1, 2))(vec!["a", "abc", "b", ""]).unwrap_err();
+        let paths: Vec<_> = errors.iter().map(|e| e.path.to_json_pointer()).collect();
+        assert_eq!(paths, vec!["/1", "/3"]);
+        assert_eq!(each(range(1, 2))(vec![1, 2]), Ok(vec![1, 2]));
+    }
+
+    #[test]
+    fn test_range() {
+        assert_eq!(range(1, 3)(3), Ok(3));
+        let error = range(1, 3)(4).unwrap_err();
+        assert_eq!(error.code, "range");
+        assert_eq!(error.to_string(), "must be between 1 and 3, got 4");
+    }
+}
@@ -0,0 +1,35 @@
+//! Support types of the validators with `severity = warn`
+
+use std::ops::Deref;
+
+/// A validated value together with the warnings reported while validating it
+///
+/// Yielded by `validate_with_warnings`, which is generated when some validator has
+/// `severity = warn`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Validated<T, W> {
+    /// The validated value
+    pub value: T,
+    /// Failures of the `severity = warn` validators
+    pub warnings: W,
+}
+
+impl<T, W> Validated<T, W> {
+    /// Drops the warnings
+    pub fn into_inner(self) -> T {
+        self.value
+    }
+
+    /// Splits into the value and the warnings
+    pub fn into_parts(self) -> (T, W) {
+        (self.value, self.warnings)
+    }
+}
+
+impl<T, W> Deref for Validated<T, W> {
+    type Target = T;
+
+    fn deref(&self) -> &T {
+        &self.value
+    }
+}
@@ -1,3 +1,5 @@
+use valibuk::sanitizers::{collapse_whitespace, lowercase, trim, uppercase};
+use valibuk::validators::{each, each_nested};
 use valibuk::Validated;
 
 fn is_positive(i: i32) -> Result<i32, String> {
@@ -18,10 +20,41 @@ fn is_at_least<'a>(n: usize) -> impl Fn(&'a str) -> Result<&'a str, String> {
     }
 }
 
-fn is_at_least_3<'a>(a: &'a str) -> Result<&str, String> {
+fn is_at_least_3<'a>(a: &'a str) -> Result<&'a str, String> {
     is_at_least::<'a>(3)(a)
 }
 
+/// Minimal executor for the async tests, polls the future until it completes
+fn block_on<F: std::future::Future>(future: F) -> F::Output {
+    let mut future = std::pin::pin!(future);
+    let mut cx = std::task::Context::from_waker(std::task::Waker::noop());
+    loop {
+        if let std::task::Poll::Ready(output) = future.as_mut().poll(&mut cx) {
+            return output;
+        }
+    }
+}
+
+/// Future that is pending on its first poll, to exercise concurrently awaited validators
+struct YieldOnce(bool);
+
+impl std::future::Future for YieldOnce {
+    type Output = ();
+
+    fn poll(
+        mut self: std::pin::Pin<&mut Self>,
+        cx: &mut std::task::Context<'_>,
+    ) -> std::task::Poll<()> {
+        if self.0 {
+            std::task::Poll::Ready(())
+        } else {
+            self.0 = true;
+            cx.waker().wake_by_ref();
+            std::task::Poll::Pending
+        }
+    }
+}
+
 #[test]
 fn ui() {
     let t = trybuild::TestCases::new();
@@ -110,8 +143,1514 @@ fn test_inline_validator() {
     {
         // negative case
         let a: i32 = -1;
-        let instance: Result<A, Vec<String>> = A::try_from(UnvalidatedA { a });
+        let instance: Result<A, Vec<String>> = A::try_from(UnvalidatedA { a }).map_err(Vec::from);
         let expected = Err(vec!["validation err".to_string()]);
         assert_eq!(instance, expected);
     }
 }
+
+#[test]
+fn test_predicate_validator() {
+    #[derive(Validated, Debug, PartialEq)]
+    struct A {
+        #[default = "  "]
+        #[sanitizer(trim)]
+        #[validator(|s: &String| !s.is_empty(), "empty".to_string())]
+        name: String,
+    }
+    let instance = A::try_from(UnvalidatedA {
+        name: Some(" a ".to_string()),
+    })
+    .expect("valid instance");
+    assert_eq!(instance.name, "a");
+    let errors = A::try_from(UnvalidatedA { name: None }).unwrap_err();
+    assert_eq!(errors.into_vec(), vec!["empty".to_string()]);
+}
+
+#[test]
+fn test_context() {
+    struct Tenants {
+        allowed: Vec<&'static str>,
+        max_quota: u32,
+    }
+
+    fn is_allowed_tenant(tenant: String, ctx: &Tenants) -> Result<String, String> {
+        if ctx.allowed.contains(&tenant.as_str()) {
+            Ok(tenant)
+        } else {
+            Err(format!("unknown tenant {}", tenant))
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation(context = Tenants)]
+    struct A {
+        #[validator(is_allowed_tenant, context)]
+        tenant: String,
+        #[validator(|q: &u32, ctx: &Tenants| *q <= ctx.max_quota, "over quota".to_string(), context)]
+        quota: u32,
+        #[validator(is_positive)]
+        a: i32,
+    }
+    let ctx = Tenants {
+        allowed: vec!["acme"],
+        max_quota: 10,
+    };
+    {
+        // positive case
+        let instance = A::validate_with(
+            UnvalidatedA {
+                tenant: "acme".to_string(),
+                quota: 10,
+                a: 1,
+            },
+            &ctx,
+        )
+        .expect("valid instance");
+        assert_eq!(instance.tenant, "acme");
+        assert_eq!(instance.quota, 10);
+        assert_eq!(instance.a, 1);
+    }
+    {
+        // negative case, both the context and the context-free validators fail
+        let errors = A::validate_with(
+            UnvalidatedA {
+                tenant: "umbrella".to_string(),
+                quota: 11,
+                a: -1,
+            },
+            &ctx,
+        )
+        .expect_err("invalid instance");
+        assert_eq!(
+            errors.into_vec(),
+            vec![
+                "unknown tenant umbrella".to_string(),
+                "over quota".to_string(),
+                "wrong".to_string()
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_async_validators() {
+    use std::cell::RefCell;
+
+    /// In-memory stand-in for the user database
+    struct UserStore {
+        taken: Vec<&'static str>,
+        lookups: RefCell<Vec<String>>,
+    }
+
+    async fn is_unique_username(username: String, store: &UserStore) -> Result<String, String> {
+        store
+            .lookups
+            .borrow_mut()
+            .push(format!("start {}", username));
+        YieldOnce(false).await;
+        store
+            .lookups
+            .borrow_mut()
+            .push(format!("finish {}", username));
+        if store.taken.contains(&username.as_str()) {
+            Err(format!("{} is taken", username))
+        } else {
+            Ok(username)
+        }
+    }
+
+    async fn is_unique_email(email: String, store: &UserStore) -> Result<String, String> {
+        store.lookups.borrow_mut().push(format!("start {}", email));
+        YieldOnce(false).await;
+        store.lookups.borrow_mut().push(format!("finish {}", email));
+        if store.taken.contains(&email.as_str()) {
+            Err(format!("{} is taken", email))
+        } else {
+            Ok(email)
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation(context = UserStore)]
+    struct A {
+        #[validator(async is_unique_username, context)]
+        username: String,
+        #[validator(is_positive)]
+        age: i32,
+        #[validator(async is_unique_email, context)]
+        email: String,
+    }
+    let store = UserStore {
+        taken: vec!["bob", "bob@example.com"],
+        lookups: RefCell::new(Vec::new()),
+    };
+    {
+        // positive case
+        let instance = block_on(A::validate_async(
+            UnvalidatedA {
+                username: "alice".to_string(),
+                age: 30,
+                email: "alice@example.com".to_string(),
+            },
+            &store,
+        ))
+        .expect("valid instance");
+        assert_eq!(instance.username, "alice");
+        assert_eq!(instance.age, 30);
+        assert_eq!(instance.email, "alice@example.com");
+        assert_eq!(
+            *store.lookups.borrow(),
+            vec![
+                "start alice".to_string(),
+                "start alice@example.com".to_string(),
+                "finish alice".to_string(),
+                "finish alice@example.com".to_string(),
+            ],
+            "both lookups are started before either of them completes"
+        );
+    }
+    {
+        // negative case, errors are collected in field order
+        let errors = block_on(A::validate_async(
+            UnvalidatedA {
+                username: "bob".to_string(),
+                age: -1,
+                email: "bob@example.com".to_string(),
+            },
+            &store,
+        ))
+        .expect_err("invalid instance");
+        assert_eq!(
+            errors.into_vec(),
+            vec![
+                "bob is taken".to_string(),
+                "wrong".to_string(),
+                "bob@example.com is taken".to_string()
+            ]
+        );
+    }
+}
+
+#[test]
+fn test_async_validator_without_context() {
+    async fn is_not_empty(s: String) -> Result<String, String> {
+        if s.is_empty() {
+            Err("empty".to_string())
+        } else {
+            Ok(s)
+        }
+    }
+
+    #[derive(Validated)]
+    struct A {
+        #[validator(async is_not_empty)]
+        a: String,
+    }
+    let instance = block_on(A::validate_async(UnvalidatedA { a: "a".to_string() }));
+    assert_eq!(instance.expect("valid instance").a, "a");
+    let errors = block_on(A::validate_async(UnvalidatedA { a: String::new() }));
+    assert_eq!(errors.err().map(Vec::from), Some(vec!["empty".to_string()]));
+}
+
+#[test]
+fn test_sanitizers() {
+    fn is_email(s: String) -> Result<String, String> {
+        if s.contains('@') && !s.contains(' ') {
+            Ok(s)
+        } else {
+            Err(format!("{} is not an email", s))
+        }
+    }
+
+    fn strip_dashes(s: String) -> String {
+        s.replace('-', "")
+    }
+
+    #[derive(Validated, Debug)]
+    struct A {
+        #[sanitizer(trim, lowercase)]
+        #[validator(is_email)]
+        email: String,
+        #[sanitizer(collapse_whitespace, strip_dashes)]
+        name: String,
+    }
+    {
+        // positive case, the validated struct holds the normalized data
+        let instance = A::try_from(UnvalidatedA {
+            email: "  Alice@Example.COM ".to_string(),
+            name: "Mary-Jane   Watson".to_string(),
+        })
+        .expect("valid instance");
+        assert_eq!(instance.email, "alice@example.com");
+        assert_eq!(instance.name, "MaryJane Watson");
+    }
+    {
+        // negative case, the validator receives the sanitized value
+        let errors = A::try_from(UnvalidatedA {
+            email: " Alice ".to_string(),
+            name: String::new(),
+        })
+        .expect_err("invalid instance");
+        assert_eq!(errors.into_vec(), vec!["alice is not an email".to_string()]);
+    }
+}
+
+#[test]
+fn test_sanitizer_named_like_builtin() {
+    // shadows the imported built-in
+    fn lowercase(s: String) -> String {
+        s.replace(' ', "_")
+    }
+
+    #[derive(Validated, Debug)]
+    struct A {
+        #[sanitizer(lowercase)]
+        name: String,
+    }
+    let instance = A::try_from(UnvalidatedA {
+        name: "Mary Jane".to_string(),
+    })
+    .expect("valid instance");
+    assert_eq!(instance.name, "Mary_Jane");
+}
+
+#[test]
+fn test_default() {
+    fn is_valid_port(port: u16) -> Result<u16, String> {
+        if port >= 1024 {
+            Ok(port)
+        } else {
+            Err(format!("port {} is reserved", port))
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    struct A {
+        #[default = 8080]
+        #[validator(is_valid_port)]
+        port: u16,
+        #[default = "localhost"]
+        #[sanitizer(lowercase)]
+        host: String,
+        #[default]
+        retries: u8,
+        #[default(vec!["admin".to_string()])]
+        roles: Vec<String>,
+    }
+    {
+        // defaults are filled in
+        let instance = A::try_from(UnvalidatedA {
+            port: None,
+            host: None,
+            retries: None,
+            roles: None,
+        })
+        .expect("valid instance");
+        assert_eq!(instance.port, 8080);
+        assert_eq!(instance.host, "localhost");
+        assert_eq!(instance.retries, 0);
+        assert_eq!(instance.roles, vec!["admin".to_string()]);
+    }
+    {
+        // provided values are used, sanitized and validated
+        let instance = A::try_from(UnvalidatedA {
+            port: Some(9000),
+            host: Some("EXAMPLE.com".to_string()),
+            retries: Some(3),
+            roles: Some(Vec::new()),
+        })
+        .expect("valid instance");
+        assert_eq!(instance.port, 9000);
+        assert_eq!(instance.host, "example.com");
+        assert_eq!(instance.retries, 3);
+        assert!(instance.roles.is_empty());
+        let errors = A::try_from(UnvalidatedA {
+            port: Some(80),
+            host: None,
+            retries: None,
+            roles: None,
+        })
+        .expect_err("invalid instance");
+        assert_eq!(errors.into_vec(), vec!["port 80 is reserved".to_string()]);
+    }
+}
+
+#[test]
+fn test_validation_default_with_derive_default() {
+    #[derive(Validated, Default, Debug)]
+    struct A {
+        #[validation_default = 5]
+        #[validator(is_positive)]
+        a: i32,
+        #[validation_default]
+        b: String,
+    }
+    let instance = A::try_from(UnvalidatedA { a: None, b: None }).expect("valid instance");
+    assert_eq!(instance.a, 5);
+    assert_eq!(A::default().a, 0);
+}
+
+#[test]
+fn test_builder() {
+    use valibuk::builder::{BuilderError, MissingField};
+    use valibuk::error::ValidationErrors;
+
+    #[derive(Validated, Debug)]
+    #[validation(builder)]
+    struct A {
+        #[validator(is_positive)]
+        a: i32,
+        b: String,
+        #[default = 3]
+        c: u8,
+    }
+    {
+        // positive case, fields with a default are optional
+        let instance = UnvalidatedA::builder()
+            .a(1)
+            .b("x")
+            .validate()
+            .expect("valid instance");
+        assert_eq!(instance.a, 1);
+        assert_eq!(instance.b, "x");
+        assert_eq!(instance.c, 3);
+        let unvalidated = UnvalidatedA::builder()
+            .b("x")
+            .c(4)
+            .a(1)
+            .build()
+            .expect("all fields set");
+        assert_eq!(unvalidated.c, Some(4));
+    }
+    {
+        // negative cases
+        let missing = UnvalidatedA::builder().a(1).validate();
+        assert_eq!(
+            missing.err(),
+            Some(BuilderError::MissingField(MissingField("b")))
+        );
+        let invalid = UnvalidatedA::builder().a(-1).b("x").validate();
+        let mut expected = ValidationErrors::new();
+        expected.push("a", "wrong".to_string());
+        assert_eq!(invalid.err(), Some(BuilderError::Invalid(expected)));
+    }
+}
+
+#[test]
+fn test_builder_with_context() {
+    fn is_below(i: i32, max: &i32) -> Result<i32, String> {
+        if i < *max {
+            Ok(i)
+        } else {
+            Err("too large".to_string())
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation(builder, context = i32)]
+    struct A {
+        #[validator(is_below, context)]
+        a: i32,
+    }
+    let instance = UnvalidatedA::builder().a(1).validate(&10);
+    assert_eq!(instance.expect("valid instance").a, 1);
+    let instance = UnvalidatedA::builder().a(100).validate(&10);
+    assert!(instance.is_err());
+}
+
+#[test]
+fn test_typestate_builder() {
+    #[derive(Validated, Debug)]
+    #[validation(builder = typestate)]
+    struct A<'a, T> {
+        #[validator(is_positive)]
+        a: i32,
+        b: &'a str,
+        #[default]
+        c: Option<T>,
+    }
+    {
+        // positive case, setters can be called in any order
+        let instance = UnvalidatedA::builder()
+            .b("x")
+            .c(Some(1u8))
+            .a(1)
+            .validate()
+            .expect("valid instance");
+        assert_eq!(instance.a, 1);
+        assert_eq!(instance.b, "x");
+        assert_eq!(instance.c, Some(1));
+        let unvalidated: UnvalidatedA<u8> = UnvalidatedA::builder().a(2).b("y").build();
+        assert_eq!(unvalidated.a, 2);
+        assert_eq!(unvalidated.c, None);
+    }
+    {
+        // negative case
+        let errors = UnvalidatedA::<()>::builder()
+            .a(-1)
+            .b("x")
+            .validate()
+            .expect_err("invalid instance");
+        assert_eq!(errors.into_vec(), vec!["wrong".to_string()]);
+    }
+}
+
+#[test]
+fn test_setters() {
+    fn is_email(s: String) -> Result<String, String> {
+        if s.contains('@') {
+            Ok(s)
+        } else {
+            Err(format!("{} is not an email", s))
+        }
+    }
+
+    async fn is_not_empty(s: String) -> Result<String, String> {
+        if s.is_empty() {
+            Err("empty".to_string())
+        } else {
+            Ok(s)
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation(setters)]
+    struct A {
+        #[sanitizer(trim)]
+        #[validator(is_email)]
+        email: String,
+        #[validator(async is_not_empty)]
+        name: String,
+        age: u8,
+    }
+    let mut a = block_on(A::validate_async(UnvalidatedA {
+        email: "a@example.com".to_string(),
+        name: "a".to_string(),
+        age: 1,
+    }))
+    .expect("valid instance");
+    {
+        // positive case, the new value is sanitized and validated
+        a.try_set_email(" b@example.com ".to_string())
+            .expect("valid email");
+        assert_eq!(a.email, "b@example.com");
+        block_on(a.try_set_name("b".to_string())).expect("valid name");
+        assert_eq!(a.name, "b");
+        a.try_set_age(2).expect("age is not validated");
+        assert_eq!(a.age, 2);
+    }
+    {
+        // negative case, the instance is left untouched
+        let error = a
+            .try_set_email("invalid".to_string())
+            .expect_err("invalid email");
+        assert_eq!(error, "invalid is not an email");
+        assert_eq!(a.email, "b@example.com");
+        let error = block_on(a.try_set_name(String::new())).expect_err("invalid name");
+        assert_eq!(error, "empty");
+        assert_eq!(a.name, "b");
+    }
+}
+
+#[test]
+fn test_from_validated() {
+    #[derive(Debug, Clone, PartialEq)]
+    struct Email(String);
+
+    fn parse_email(s: String) -> Result<Email, String> {
+        if s.contains('@') {
+            Ok(Email(s))
+        } else {
+            Err(format!("{} is not an email", s))
+        }
+    }
+
+    #[derive(Validated, Debug, Clone, PartialEq)]
+    struct A {
+        #[validator(parse_email, from = String, into = |e: Email| e.0)]
+        email: Email,
+        #[validator(is_positive)]
+        a: i32,
+        #[default = 3]
+        b: u8,
+    }
+    let a = A::try_from(UnvalidatedA {
+        email: "a@example.com".to_string(),
+        a: 1,
+        b: None,
+    })
+    .expect("valid instance");
+    assert_eq!(a.email, Email("a@example.com".to_string()));
+    {
+        // round trip is lossless
+        let unvalidated = UnvalidatedA::from(a.clone());
+        assert_eq!(unvalidated.email, "a@example.com");
+        assert_eq!(unvalidated.a, 1);
+        assert_eq!(unvalidated.b, Some(3));
+        assert_eq!(A::try_from(unvalidated), Ok(a.clone()));
+    }
+    {
+        // edit workflow, load valid record, edit, revalidate
+        let mut unvalidated: UnvalidatedA = a.into();
+        unvalidated.email = "invalid".to_string();
+        assert_eq!(
+            A::try_from(unvalidated).map_err(Vec::from),
+            Err(vec!["invalid is not an email".to_string()])
+        );
+    }
+}
+
+mod sealed {
+    use valibuk::Validated;
+
+    fn is_positive(i: i32) -> Result<i32, String> {
+        if i > 0 {
+            Ok(i)
+        } else {
+            Err("wrong".to_string())
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation(sealed, setters)]
+    pub struct A {
+        #[validator(is_positive)]
+        a: i32,
+        b: String,
+    }
+}
+
+#[test]
+fn test_sealed() {
+    use sealed::{UnvalidatedA, A};
+
+    let mut instance = A::try_from(UnvalidatedA {
+        a: 1,
+        b: "b".to_string(),
+    })
+    .expect("valid instance");
+    assert_eq!(*instance.a(), 1);
+    assert_eq!(instance.b(), "b");
+    assert!(instance.try_set_a(-1).is_err());
+    assert_eq!(*instance.a(), 1);
+    assert!(A::try_from(UnvalidatedA {
+        a: -1,
+        b: "b".to_string(),
+    })
+    .is_err());
+}
+
+#[test]
+fn test_patch() {
+    fn is_email(s: String) -> Result<String, String> {
+        if s.contains('@') {
+            Ok(s)
+        } else {
+            Err(format!("{} is not an email", s))
+        }
+    }
+
+    #[derive(Validated, Debug, Clone, PartialEq)]
+    #[validation(patch)]
+    struct A {
+        #[sanitizer(trim)]
+        #[validator(is_email)]
+        email: String,
+        #[validator(is_positive)]
+        a: i32,
+        name: String,
+    }
+    let a = A::try_from(UnvalidatedA {
+        email: "a@example.com".to_string(),
+        a: 1,
+        name: "a".to_string(),
+    })
+    .expect("valid instance");
+    {
+        // empty patch keeps everything
+        assert_eq!(a.clone().apply_patch(PatchA::default()), Ok(a.clone()));
+    }
+    {
+        // only the provided fields are sanitized and validated
+        let patched = a
+            .clone()
+            .apply_patch(PatchA {
+                email: Some(" b@example.com ".to_string()),
+                name: Some("b".to_string()),
+                ..Default::default()
+            })
+            .expect("valid patch");
+        assert_eq!(patched.email, "b@example.com");
+        assert_eq!(patched.a, 1);
+        assert_eq!(patched.name, "b");
+    }
+    {
+        // errors of all the provided fields are collected
+        let errors = a
+            .apply_patch(PatchA {
+                email: Some("invalid".to_string()),
+                a: Some(-1),
+                name: None,
+            })
+            .expect_err("invalid patch");
+        assert_eq!(
+            errors.into_vec(),
+            vec!["invalid is not an email".to_string(), "wrong".to_string()]
+        );
+    }
+}
+
+#[test]
+fn test_groups() {
+    use valibuk::groups::{Create, Group, Update};
+
+    struct Publish;
+
+    impl Group for Publish {}
+
+    fn is_absent(id: Option<u64>) -> Result<Option<u64>, String> {
+        match id {
+            None => Ok(id),
+            Some(_) => Err("id must not be set".to_string()),
+        }
+    }
+
+    fn is_present(id: Option<u64>) -> Result<Option<u64>, String> {
+        match id {
+            Some(_) => Ok(id),
+            None => Err("id is required".to_string()),
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    struct A {
+        #[validator(is_absent, groups(Create))]
+        #[validator(is_present, groups(Update, Publish))]
+        id: Option<u64>,
+        #[validator(is_positive)]
+        a: i32,
+        #[validator(|s: String| if s.is_empty() { Err("empty".to_string()) } else { Ok(s) }, groups(Publish))]
+        title: String,
+    }
+    let new = |id, a, title: &str| UnvalidatedA {
+        id,
+        a,
+        title: title.to_string(),
+    };
+    {
+        // positive cases
+        let instance = A::validate_for::<Create>(new(None, 1, "")).expect("valid for create");
+        assert_eq!(instance.id, None);
+        let instance = A::validate_for::<Update>(new(Some(1), 1, "")).expect("valid for update");
+        assert_eq!(instance.id, Some(1));
+        assert!(A::validate_for::<Publish>(new(Some(1), 1, "t")).is_ok());
+        // validators with groups are not run by TryFrom
+        assert!(A::try_from(new(Some(1), 1, "")).is_ok());
+    }
+    {
+        // negative cases, ungrouped validators always run
+        let errors = A::validate_for::<Create>(new(Some(1), -1, "")).expect_err("invalid");
+        assert_eq!(
+            errors.into_vec(),
+            vec!["id must not be set".to_string(), "wrong".to_string()]
+        );
+        let errors = A::validate_for::<Update>(new(None, 1, "")).expect_err("invalid");
+        assert_eq!(errors.into_vec(), vec!["id is required".to_string()]);
+        let errors = A::validate_for::<Publish>(new(None, 1, "")).expect_err("invalid");
+        assert_eq!(
+            errors.into_vec(),
+            vec!["id is required".to_string(), "empty".to_string()]
+        );
+    }
+}
+
+#[test]
+fn test_warnings() {
+    #[allow(clippy::ptr_arg)]
+    fn weak_password(p: &String) -> Result<(), String> {
+        if p.len() < 8 {
+            Err("password is weak".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn is_not_deprecated(_: &Option<String>) -> bool {
+        false
+    }
+
+    #[derive(Validated, Debug)]
+    struct A {
+        #[validator(weak_password, severity = warn)]
+        password: String,
+        #[validator(is_positive)]
+        a: i32,
+        #[validator(is_not_deprecated, "legacy is deprecated".to_string(), severity = warn)]
+        legacy: Option<String>,
+    }
+    {
+        // warnings do not prevent construction
+        let validated = A::validate_with_warnings(UnvalidatedA {
+            password: "short".to_string(),
+            a: 1,
+            legacy: None,
+        })
+        .expect("valid instance");
+        assert_eq!(
+            validated.warnings.clone().into_vec(),
+            vec![
+                "password is weak".to_string(),
+                "legacy is deprecated".to_string()
+            ]
+        );
+        assert_eq!(validated.a, 1);
+        let (instance, _) = validated.into_parts();
+        assert_eq!(instance.password, "short");
+        // and are ignored by TryFrom
+        assert!(A::try_from(UnvalidatedA {
+            password: "short".to_string(),
+            a: 1,
+            legacy: None,
+        })
+        .is_ok());
+    }
+    {
+        // errors still fail
+        let errors = A::validate_with_warnings(UnvalidatedA {
+            password: "short".to_string(),
+            a: -1,
+            legacy: None,
+        })
+        .expect_err("invalid instance");
+        assert_eq!(errors.into_vec(), vec!["wrong".to_string()]);
+    }
+}
+
+#[test]
+fn test_warnings_fields_named_like_locals() {
+    struct Limits {
+        soft_max: i32,
+    }
+
+    fn is_below_soft_max(v: &i32, limits: &Limits) -> Result<(), String> {
+        if *v > limits.soft_max {
+            Err("above the soft maximum".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation(context = Limits)]
+    struct A {
+        #[validator(is_positive)]
+        warnings: i32,
+        #[validator(is_below_soft_max, context, severity = warn)]
+        ctx: i32,
+    }
+    let validated = A::validate_with_warnings(
+        UnvalidatedA {
+            warnings: 1,
+            ctx: 11,
+        },
+        &Limits { soft_max: 10 },
+    )
+    .expect("valid instance");
+    assert_eq!(
+        validated.warnings.clone().into_vec(),
+        vec!["above the soft maximum".to_string()]
+    );
+    let (instance, _) = validated.into_parts();
+    assert_eq!((instance.warnings, instance.ctx), (1, 11));
+}
+
+#[test]
+fn test_conditional_validators() {
+    fn valid_vat(vat_id: String) -> Result<String, String> {
+        if vat_id.starts_with("DE") && vat_id.len() == 11 {
+            Ok(vat_id)
+        } else {
+            Err(format!("{} is not a valid VAT ID", vat_id))
+        }
+    }
+
+    fn is_german(u: &UnvalidatedA) -> bool {
+        u.country == "DE"
+    }
+
+    #[derive(Validated, Debug)]
+    struct A {
+        #[sanitizer(uppercase)]
+        country: String,
+        #[validator(valid_vat, when = |u: &UnvalidatedA| u.country.eq_ignore_ascii_case("de"))]
+        vat_id: String,
+        #[validator(is_positive, when = is_german)]
+        a: i32,
+    }
+    let new = |country: &str, vat_id: &str, a| UnvalidatedA {
+        country: country.to_string(),
+        vat_id: vat_id.to_string(),
+        a,
+    };
+    {
+        // positive cases
+        let instance = A::try_from(new("US", "", -1)).expect("not validated outside DE");
+        assert_eq!(instance.vat_id, "");
+        assert_eq!(instance.country, "US");
+        assert!(A::try_from(new("de", "DE123456789", -1)).is_ok());
+    }
+    {
+        // negative cases, the condition sees the input before it is sanitized
+        let errors = A::try_from(new("de", "123", -1)).expect_err("invalid VAT ID");
+        assert_eq!(
+            errors.into_vec(),
+            vec!["123 is not a valid VAT ID".to_string()]
+        );
+        let errors = A::try_from(new("DE", "123", -1)).expect_err("invalid instance");
+        assert_eq!(
+            errors.into_vec(),
+            vec!["123 is not a valid VAT ID".to_string(), "wrong".to_string()]
+        );
+    }
+}
+
+#[test]
+fn test_ref_validators() {
+    fn is_not_empty(s: &str) -> Result<(), String> {
+        if s.is_empty() {
+            Err("empty".to_string())
+        } else {
+            Ok(())
+        }
+    }
+
+    fn is_short(v: &[u8]) -> bool {
+        v.len() < 3
+    }
+
+    #[derive(Validated, Debug)]
+    struct A {
+        #[validator(ref |s: &String| is_not_empty(s))]
+        name: String,
+        #[default(vec![1, 2, 3])]
+        #[validator(ref |v: &Vec<u8>| is_short(v), "too long".to_string())]
+        tags: Vec<u8>,
+        #[validator(is_positive)]
+        a: i32,
+    }
+    {
+        // check borrows the unvalidated struct and only runs the ref validators
+        let unvalidated = UnvalidatedA {
+            name: String::new(),
+            tags: None,
+            a: -1,
+        };
+        assert_eq!(
+            unvalidated.check().map_err(Vec::from),
+            Err(vec!["empty".to_string(), "too long".to_string()])
+        );
+        assert_eq!(
+            A::try_from(unvalidated)
+                .expect_err("invalid instance")
+                .into_vec(),
+            vec![
+                "empty".to_string(),
+                "too long".to_string(),
+                "wrong".to_string()
+            ]
+        );
+    }
+    {
+        // positive case
+        let unvalidated = UnvalidatedA {
+            name: "a".to_string(),
+            tags: Some(vec![1]),
+            a: -1,
+        };
+        assert_eq!(unvalidated.check(), Ok(()));
+        let unvalidated = UnvalidatedA {
+            a: 1,
+            ..unvalidated
+        };
+        let instance = A::try_from(unvalidated).expect("valid instance");
+        assert_eq!(instance.name, "a");
+        assert_eq!(instance.tags, vec![1]);
+    }
+}
+
+#[test]
+fn test_partial() {
+    use valibuk::partial::PartialResult;
+
+    #[derive(Validated, Debug)]
+    #[validation(partial)]
+    struct A<'a> {
+        #[validator(is_positive)]
+        a: i32,
+        #[validator(is_at_least_3)]
+        b: &'a str,
+        c: u8,
+    }
+    {
+        // positive case
+        let result = A::validate_partial(UnvalidatedA {
+            a: 1,
+            b: "bbb",
+            c: 2,
+        });
+        assert!(result.is_valid());
+        let instance = result.into_result().expect("valid instance");
+        assert_eq!(instance.b, "bbb");
+    }
+    {
+        // negative case, the valid fields are kept
+        match A::validate_partial(UnvalidatedA {
+            a: -1,
+            b: "bbb",
+            c: 2,
+        }) {
+            PartialResult::Invalid { fields, errors } => {
+                assert_eq!(fields.a, None);
+                assert_eq!(fields.b, Some("bbb"));
+                assert_eq!(fields.c, Some(2));
+                assert_eq!(errors.into_vec(), vec!["wrong".to_string()]);
+            }
+            PartialResult::Valid(_) => panic!("invalid instance"),
+        }
+        let errors = A::validate_partial(UnvalidatedA { a: -1, b: "", c: 2 })
+            .into_result()
+            .expect_err("invalid instance");
+        assert_eq!(
+            errors.into_vec(),
+            vec!["wrong".to_string(), "wrong".to_string()]
+        );
+    }
+}
+
+#[test]
+fn test_validation_errors() {
+    #[derive(Validated, Debug)]
+    struct A {
+        #[validator(is_positive)]
+        a: i32,
+        #[validator(is_positive)]
+        b: i32,
+        #[validator(is_positive)]
+        c: i32,
+    }
+    let errors = A::try_from(UnvalidatedA { a: -1, b: 1, c: -1 }).expect_err("invalid instance");
+    assert_eq!(
+        errors
+            .iter()
+            .map(|e| e.path.to_dotted())
+            .collect::<Vec<_>>(),
+        vec!["a", "c"]
+    );
+    assert_eq!(errors.to_string(), "a: wrong\nc: wrong");
+    assert_eq!(errors.field("c").count(), 1);
+    let raw: Vec<String> = errors.into_iter().collect();
+    assert_eq!(raw, vec!["wrong".to_string(), "wrong".to_string()]);
+}
+
+#[test]
+fn test_field_error_types() {
+    #[derive(Debug, PartialEq)]
+    struct EmailError;
+
+    #[derive(Debug, PartialEq)]
+    enum StructError {
+        Email,
+        Other(String),
+    }
+
+    impl From<EmailError> for StructError {
+        fn from(_: EmailError) -> Self {
+            StructError::Email
+        }
+    }
+
+    impl From<String> for StructError {
+        fn from(value: String) -> Self {
+            StructError::Other(value)
+        }
+    }
+
+    fn is_email(s: String) -> Result<String, EmailError> {
+        if s.contains('@') {
+            Ok(s)
+        } else {
+            Err(EmailError)
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation_error(StructError)]
+    struct A {
+        #[validator(is_email)]
+        email: String,
+        #[validator(is_positive)]
+        age: i32,
+    }
+
+    // positive case
+    {
+        let a = A::try_from(UnvalidatedA {
+            email: "a@b.c".to_string(),
+            age: 1,
+        })
+        .expect("valid instance");
+        assert_eq!(a.email, "a@b.c");
+    }
+
+    // negative case
+    {
+        let errors = A::try_from(UnvalidatedA {
+            email: "abc".to_string(),
+            age: -1,
+        })
+        .expect_err("invalid instance");
+        assert_eq!(
+            errors.into_vec(),
+            vec![StructError::Email, StructError::Other("wrong".to_string())]
+        );
+    }
+}
+
+#[test]
+fn test_generated_error_enum() {
+    #[derive(Debug, PartialEq)]
+    struct EmailError;
+
+    impl std::fmt::Display for EmailError {
+        fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+            write!(f, "not an email")
+        }
+    }
+
+    fn is_email(s: String) -> Result<String, EmailError> {
+        if s.contains('@') {
+            Ok(s)
+        } else {
+            Err(EmailError)
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation_error(auto)]
+    struct A {
+        #[validator(is_email, error_type = EmailError)]
+        email: String,
+        #[validator(is_positive)]
+        age: i32,
+        name: String,
+    }
+
+    // positive case
+    {
+        let a = A::try_from(UnvalidatedA {
+            email: "a@b.c".to_string(),
+            age: 1,
+            name: "a".to_string(),
+        })
+        .expect("valid instance");
+        assert_eq!(a.email, "a@b.c");
+        assert_eq!(a.name, "a");
+    }
+
+    // negative case
+    {
+        let errors = A::try_from(UnvalidatedA {
+            email: "abc".to_string(),
+            age: -1,
+            name: "a".to_string(),
+        })
+        .expect_err("invalid instance");
+        assert_eq!(errors.to_string(), "email: not an email\nage: wrong");
+        for error in errors {
+            match error {
+                AValidationError::Email(e) => assert_eq!(e, EmailError),
+                AValidationError::Age(e) => assert_eq!(e, "wrong"),
+            }
+        }
+    }
+}
+
+#[test]
+fn test_validation_error_templates() {
+    use valibuk::message::{Templates, ValidationError};
+    use valibuk::validators::{length, range};
+
+    #[derive(Validated, Debug)]
+    #[validation_error(ValidationError)]
+    struct A {
+        #[validator(length(3, 10))]
+        name: String,
+        #[validator(range(18, 99))]
+        age: u8,
+        #[validator(is_positive)]
+        count: i32,
+    }
+
+    // positive case
+    {
+        let a = A::try_from(UnvalidatedA {
+            name: "abc".to_string(),
+            age: 18,
+            count: 1,
+        })
+        .expect("valid instance");
+        assert_eq!(a.name, "abc");
+        assert_eq!(a.age, 18);
+    }
+
+    // negative case
+    {
+        let errors = A::try_from(UnvalidatedA {
+            name: "ab".to_string(),
+            age: 100,
+            count: 0,
+        })
+        .expect_err("invalid instance");
+        let templates = Templates::new().with("length", "{min} to {max} characters please");
+        let rendered: Vec<_> = errors.iter().map(|e| e.error.render(&templates)).collect();
+        assert_eq!(
+            rendered,
+            vec![
+                "3 to 10 characters please",
+                "must be between 18 and 99, got 100",
+                "wrong",
+            ]
+        );
+        let error = errors.field("age").next().expect("age failed");
+        assert_eq!(error.code, "range");
+        assert_eq!(error.param("actual"), Some("100"));
+    }
+}
+
+#[test]
+fn test_localized_report() {
+    use valibuk::i18n::Catalogs;
+    use valibuk::message::ValidationError;
+
+    #[derive(Validated, Debug)]
+    #[validation_error(ValidationError)]
+    struct A {
+        #[validator(valibuk::validators::length(3, 10))]
+        name: String,
+        #[validator(valibuk::validators::range(18, 99))]
+        age: u8,
+        #[validator(is_positive)]
+        count: i32,
+    }
+
+    let catalogs = Catalogs::load_dir(
+        std::path::Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures/i18n"),
+        "en",
+    )
+    .expect("fixture catalogs load");
+    let errors = A::try_from(UnvalidatedA {
+        name: "ab".to_string(),
+        age: 10,
+        count: 0,
+    })
+    .expect_err("invalid instance");
+    assert_eq!(
+        catalogs.report(&errors, "de-CH").to_string(),
+        "name: muss zwischen 3 und 10 Zeichen lang sein\nage: must be at least 18\ncount: ist ungültig"
+    );
+    assert_eq!(
+        catalogs.report(&errors, "en").to_string(),
+        "name: must be 3 to 10 characters long\nage: must be at least 18\ncount: is invalid"
+    );
+}
+
+#[test]
+fn test_validator_named_like_builtin() {
+    // a user validator is not replaced by the built-in of the same name
+    fn length(max: usize) -> impl Fn(String) -> Result<String, String> {
+        move |s| {
+            if s.len() <= max {
+                Ok(s)
+            } else {
+                Err("too long".to_string())
+            }
+        }
+    }
+
+    // shadows the imported built-in
+    fn each(s: String) -> Result<String, String> {
+        if s.contains(',') {
+            Err("one at a time".to_string())
+        } else {
+            Ok(s)
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    struct A {
+        #[validator(length(3))]
+        name: String,
+        #[validator(each)]
+        tag: String,
+    }
+    let errors = A::try_from(UnvalidatedA {
+        name: "abcd".to_string(),
+        tag: "a,b".to_string(),
+    })
+    .expect_err("invalid instance");
+    assert_eq!(
+        errors.into_vec(),
+        vec!["too long".to_string(), "one at a time".to_string()]
+    );
+}
+
+#[test]
+fn test_nested_error_paths() {
+    fn is_not_empty(s: String) -> Result<String, String> {
+        if s.is_empty() {
+            Err("empty".to_string())
+        } else {
+            Ok(s)
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    struct Address {
+        #[validator(is_not_empty)]
+        city: String,
+        #[validator(each(is_not_empty), nested)]
+        lines: Vec<String>,
+    }
+
+    #[derive(Validated, Debug)]
+    struct Person {
+        #[validator(is_positive)]
+        age: i32,
+        #[validator(Address::try_from, from = UnvalidatedAddress, nested)]
+        address: Address,
+        #[validator(each_nested(Address::try_from), from = Vec<UnvalidatedAddress>, nested)]
+        previous: Vec<Address>,
+    }
+
+    let address = |city: &str, lines: &[&str]| UnvalidatedAddress {
+        city: city.to_string(),
+        lines: lines.iter().map(|l| l.to_string()).collect(),
+    };
+
+    // positive case
+    {
+        let p = Person::try_from(UnvalidatedPerson {
+            age: 1,
+            address: address("a", &["b"]),
+            previous: vec![address("c", &[])],
+        })
+        .expect("valid instance");
+        assert_eq!(p.age, 1);
+        assert_eq!(p.address.lines, vec!["b".to_string()]);
+        assert_eq!(p.previous[0].city, "c");
+    }
+
+    // negative case
+    {
+        let errors = Person::try_from(UnvalidatedPerson {
+            age: -1,
+            address: address("a", &["b", "c", ""]),
+            previous: vec![address("d", &[]), address("", &["", "e"])],
+        })
+        .expect_err("invalid instance");
+        assert_eq!(
+            errors
+                .iter()
+                .map(|e| e.path.to_json_pointer())
+                .collect::<Vec<_>>(),
+            vec![
+                "/age",
+                "/address/lines/2",
+                "/previous/1/city",
+                "/previous/1/lines/0"
+            ]
+        );
+        assert_eq!(
+            errors.to_string(),
+            "age: wrong\naddress.lines.2: empty\nprevious.1.city: empty\nprevious.1.lines.0: empty"
+        );
+    }
+}
+
+#[test]
+fn test_captured_values() {
+    fn is_long(s: String) -> Result<String, String> {
+        if s.len() >= 8 {
+            Ok(s)
+        } else {
+            Err("too short".to_string())
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation(patch)]
+    struct A {
+        #[validator(is_positive, capture_value)]
+        age: i32,
+        #[sensitive]
+        #[validator(is_long, capture_value)]
+        password: String,
+        #[validator(is_positive)]
+        count: i32,
+    }
+
+    // negative case
+    {
+        let errors = A::try_from(UnvalidatedA {
+            age: -1,
+            password: "hunter2".to_string(),
+            count: 0,
+        })
+        .expect_err("invalid instance");
+        let values: Vec<_> = errors.iter().map(|e| e.value.as_deref()).collect();
+        assert_eq!(values, vec![Some("-1"), Some("[redacted]"), None]);
+        assert_eq!(
+            errors.to_string(),
+            "age: wrong (got -1)\npassword: too short (got [redacted])\ncount: wrong"
+        );
+    }
+
+    // patch case
+    {
+        let a = A::try_from(UnvalidatedA {
+            age: 1,
+            password: "correct horse".to_string(),
+            count: 1,
+        })
+        .expect("valid instance");
+        let errors = a
+            .apply_patch(PatchA {
+                age: Some(-2),
+                ..Default::default()
+            })
+            .expect_err("invalid patch");
+        assert_eq!(errors.to_string(), "age: wrong (got -2)");
+    }
+}
+
+#[test]
+fn test_sensitive_nested_values() {
+    fn is_long(s: String) -> Result<String, String> {
+        if s.len() >= 8 {
+            Ok(s)
+        } else {
+            Err("too short".to_string())
+        }
+    }
+
+    #[derive(Validated, Debug)]
+    struct Creds {
+        #[validator(is_long, capture_value)]
+        password: String,
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation(setters)]
+    struct Login {
+        #[sensitive]
+        #[validator(Creds::try_from, from = UnvalidatedCreds, nested)]
+        creds: Creds,
+        #[validator(Creds::try_from, from = UnvalidatedCreds, nested)]
+        public: Creds,
+    }
+    let creds = || UnvalidatedCreds {
+        password: "hunter2".to_string(),
+    };
+    let errors = Login::try_from(UnvalidatedLogin {
+        creds: creds(),
+        public: creds(),
+    })
+    .expect_err("invalid instance");
+    assert_eq!(
+        errors.to_string(),
+        "creds.password: too short (got [redacted])\npublic.password: too short (got \"hunter2\")"
+    );
+
+    // setters redact the nested values too
+    let valid = || UnvalidatedCreds {
+        password: "correct horse".to_string(),
+    };
+    let mut login = Login::try_from(UnvalidatedLogin {
+        creds: valid(),
+        public: valid(),
+    })
+    .expect("valid instance");
+    assert_eq!(login.public.password, "correct horse");
+    let errors = login.try_set_creds(creds()).expect_err("invalid creds");
+    assert_eq!(errors.to_string(), "password: too short (got [redacted])");
+}
+
+#[test]
+fn test_max_errors() {
+    #[derive(Validated, Debug)]
+    #[validation(max_errors = 3)]
+    struct Import {
+        #[validator(each(is_positive), nested)]
+        rows: Vec<i32>,
+        #[validator(is_positive)]
+        count: i32,
+    }
+
+    #[derive(Validated, Debug)]
+    #[validation(dedup_errors)]
+    struct Dedup {
+        #[validator(each(is_positive), nested)]
+        rows: Vec<i32>,
+        #[validator(is_positive)]
+        count: i32,
+    }
+
+    // limit case
+    {
+        let errors = Import::try_from(UnvalidatedImport {
+            rows: vec![-1; 10],
+            count: -1,
+        })
+        .expect_err("invalid instance");
+        assert_eq!(errors.len(), 3);
+        assert_eq!(errors.omitted(), 8);
+        assert_eq!(
+            errors.to_string(),
+            "rows.0: wrong\nrows.1: wrong\nrows.2: wrong\n8 more errors"
+        );
+    }
+
+    // deduplication case
+    {
+        let errors = Dedup::try_from(UnvalidatedDedup {
+            rows: vec![1, -1, -1],
+            count: -1,
+        })
+        .expect_err("invalid instance");
+        // `count` fails with the same error as `rows`, but it is a different field
+        assert_eq!(
+            errors.to_string(),
+            "rows.1: wrong\ncount: wrong\n1 more errors"
+        );
+    }
+
+    // deduplication case with the generated error enum
+    {
+        #[derive(Validated, Debug)]
+        #[validation_error(auto)]
+        #[validation(dedup_errors)]
+        struct AutoDedup {
+            #[validator(each(is_positive), nested)]
+            rows: Vec<i32>,
+        }
+
+        let errors = AutoDedup::try_from(UnvalidatedAutoDedup {
+            rows: vec![-1, 1, -1],
+        })
+        .expect_err("invalid instance");
+        assert_eq!(errors.len(), 1);
+        assert_eq!(errors.omitted(), 1);
+        assert_eq!(errors.to_string(), "rows.0: wrong\n1 more errors");
+    }
+}
@@ -0,0 +1,21 @@
+use valibuk::Validated;
+
+fn is_positive(i: i32) -> Result<i32, String> {
+    if i > 0 {
+        Ok(i)
+    } else {
+        Err("wrong".to_string())
+    }
+}
+
+#[derive(Validated)]
+#[validation_error(auto)]
+struct A {
+    #[validator(is_positive)]
+    a: i32,
+    // This should fail, because the errors of `_a` get the same variant `A` as the ones of `a`
+    #[validator(is_positive)]
+    _a: i32,
+}
+
+fn main() {}
@@ -0,0 +1,17 @@
+use valibuk::groups::Update;
+use valibuk::Validated;
+
+fn is_present(s: Option<String>) -> Result<Option<String>, String> {
+    s.ok_or_else(|| "must be present".to_string()).map(Some)
+}
+
+#[derive(Validated)]
+struct A {
+    // This should fail, because there is no group `Updte`
+    #[validator(is_present, groups(Updte))]
+    a: Option<String>,
+}
+
+fn main() {
+    let _ = A::validate_for::<Update>(UnvalidatedA { a: None });
+}
@@ -0,0 +1,14 @@
+mod sealed {
+    use valibuk::Validated;
+
+    #[derive(Validated)]
+    #[validation(sealed)]
+    pub struct A {
+        a: i32,
+    }
+}
+
+fn main() {
+    // This should fail, because outside of its module a sealed struct can only be validated
+    let _ = sealed::A { a: -5 };
+}
@@ -0,0 +1,10 @@
+use valibuk::Validated;
+
+#[derive(Validated)]
+#[validation(sealed)]
+pub struct A {
+    // This should fail, because a public field allows constructing `A` without validation
+    pub a: i32,
+}
+
+fn main() {}
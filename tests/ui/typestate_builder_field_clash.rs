@@ -0,0 +1,11 @@
+use valibuk::Validated;
+
+#[derive(Validated)]
+#[validation(builder = typestate)]
+struct A {
+    a: i32,
+    // This should fail, because `_a` is tracked by the same type parameter `__A` as `a`
+    _a: i32,
+}
+
+fn main() {}
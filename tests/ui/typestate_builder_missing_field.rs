@@ -0,0 +1,13 @@
+use valibuk::Validated;
+
+#[derive(Validated)]
+#[validation(builder = typestate)]
+struct A {
+    a: i32,
+    b: String,
+}
+
+fn main() {
+    // This should fail, because `b` was never set, so `build` does not exist yet
+    let _ = UnvalidatedA::builder().a(1).build();
+}
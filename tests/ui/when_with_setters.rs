@@ -0,0 +1,20 @@
+use valibuk::Validated;
+
+fn is_positive(i: i32) -> Result<i32, String> {
+    if i > 0 {
+        Ok(i)
+    } else {
+        Err("wrong".to_string())
+    }
+}
+
+#[derive(Validated)]
+#[validation(setters)]
+struct A {
+    enabled: bool,
+    // This should fail, because a setter cannot evaluate the condition against the whole input
+    #[validator(is_positive, when = |u: &UnvalidatedA| u.enabled)]
+    a: i32,
+}
+
+fn main() {}
@@ -1,38 +1,204 @@
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{spanned::Spanned, Error};
+use quote::{format_ident, quote, quote_spanned};
+use syn::{
+    parse::{Parse, ParseStream},
+    spanned::Spanned,
+    Error, Token,
+};
 
 #[derive(Debug)]
 pub(crate) struct ValidatedFieldDeriv<'a> {
     name: &'a syn::Ident,
+    vis: &'a syn::Visibility,
     ty: &'a syn::Type,
     custom_validation_error_ty: syn::Type,
-    field_validator: FieldValidator,
+    error_enum: Option<syn::Ident>,
+    context_ty: Option<syn::Type>,
+    field_validator: Option<FieldValidator>,
+    group_validators: Vec<FieldValidator>,
+    warn_validators: Vec<FieldValidator>,
+    sanitizers: Vec<syn::Expr>,
+    default: Option<FieldDefault>,
+    sensitive: bool,
 }
 
 impl<'a> ValidatedFieldDeriv<'a> {
-    pub fn new(field: &'a syn::Field, error: syn::Type) -> Result<ValidatedFieldDeriv<'a>, Error> {
+    pub fn new(
+        field: &'a syn::Field,
+        error: syn::Type,
+        context_ty: Option<syn::Type>,
+    ) -> Result<ValidatedFieldDeriv<'a>, Error> {
         if let Some(ref name) = field.ident {
-            let field_validator = Self::parse_field_validator(field); // dbg!(&field_validator);
+            let validators = Self::parse_field_validators(field)?;
+            for v in &validators {
+                if v.with_context && context_ty.is_none() {
+                    return Err(Error::new(
+                        v.func.span(),
+                        "validator requires a context, declare it with `#[validation(context = T)]` on the struct",
+                    ));
+                }
+            }
+            let mut field_validator = None;
+            let mut group_validators = Vec::new();
+            let mut warn_validators = Vec::new();
+            for v in validators {
+                if v.severity == Severity::Warn {
+                    if v.is_async || v.from.is_some() || v.into.is_some() || !v.groups.is_empty() {
+                        return Err(Error::new(
+                            v.func.span(),
+                            "validators with `severity = warn` can be neither async, nor change the type of the field, nor have groups",
+                        ));
+                    }
+                    warn_validators.push(v);
+                } else if !v.groups.is_empty() {
+                    if v.is_async || v.from.is_some() || v.into.is_some() {
+                        return Err(Error::new(
+                            v.func.span(),
+                            "validators with groups can be neither async nor change the type of the field",
+                        ));
+                    }
+                    group_validators.push(v);
+                } else {
+                    // the last validator without groups wins
+                    field_validator = Some(v);
+                }
+            }
+            for v in group_validators.iter().chain(&warn_validators) {
+                if v.nested {
+                    return Err(Error::new(
+                        v.func.span(),
+                        "`nested` is only supported on validators without groups or severity",
+                    ));
+                }
+                if let Some(when) = &v.when {
+                    return Err(Error::new(
+                        when.span(),
+                        "`when` is only supported on validators without groups or severity",
+                    ));
+                }
+            }
+            if let Some(FieldValidator {
+                by_ref: true,
+                from: Some(from),
+                ..
+            }) = &field_validator
+            {
+                return Err(Error::new(
+                    from.span(),
+                    "validators taking the value by reference cannot change its type",
+                ));
+            }
+            if let Some(v) = field_validator
+                .as_ref()
+                .filter(|v| v.nested && v.capture_value)
+            {
+                return Err(Error::new(
+                    v.func.span(),
+                    "`capture_value` is not supported on `nested` validators, the nested errors capture their own values",
+                ));
+            }
+            if let Some(v) = field_validator
+                .as_ref()
+                .filter(|v| v.nested && (v.by_ref || v.error.is_some()))
+            {
+                return Err(Error::new(
+                    v.func.span(),
+                    "`nested` validators can neither take the value by reference nor be predicates",
+                ));
+            }
+            if field_validator.as_ref().is_some_and(|v| v.nested) && !group_validators.is_empty() {
+                return Err(Error::new(
+                    group_validators[0].func.span(),
+                    "fields with a `nested` validator cannot have validators with groups",
+                ));
+            }
+            if let Some(FieldValidator {
+                is_async: true,
+                when: Some(when),
+                ..
+            }) = &field_validator
+            {
+                return Err(Error::new(
+                    when.span(),
+                    "`when` is not supported on async validators",
+                ));
+            }
             Ok(ValidatedFieldDeriv {
-                name: &name,
+                name,
+                vis: &field.vis,
                 ty: &field.ty,
                 custom_validation_error_ty: error,
+                error_enum: None,
+                context_ty,
                 field_validator,
+                group_validators,
+                warn_validators,
+                sanitizers: Self::parse_sanitizers(field)?,
+                default: Self::parse_default(field)?,
+                sensitive: Self::parse_sensitive(field)?,
             })
         } else {
             Err(Error::new(field.span(), "Nameless field in struct"))
         }
     }
 
-    fn parse_field_validator(field: &'a syn::Field) -> FieldValidator {
+    /// True when the field is marked with `#[sensitive]`
+    fn parse_sensitive(field: &'a syn::Field) -> Result<bool, Error> {
+        let mut sensitive = false;
+        for attr in field.attrs.iter().filter(|a| a.path.is_ident("sensitive")) {
+            if !attr.tokens.is_empty() {
+                return Err(Error::new(
+                    attr.tokens.span(),
+                    "`sensitive` takes no arguments",
+                ));
+            }
+            sensitive = true;
+        }
+        Ok(sensitive)
+    }
+
+    /// Parses all the `#[validator(...)]` attributes of the field, in order of appearance
+    fn parse_field_validators(field: &'a syn::Field) -> Result<Vec<FieldValidator>, Error> {
         field
             .attrs
             .iter()
             .filter(|a| a.path.is_ident("validator"))
-            .map(FieldValidator::from)
-            .last()
-            .unwrap_or(FieldValidator::None)
+            .map(|a| a.parse_args::<FieldValidator>())
+            .collect()
+    }
+
+    /// Parses the last `#[default ...]` or `#[validation_default ...]` attribute of the field
+    ///
+    /// The namespaced form is for structs also deriving `Default`, whose derive claims
+    /// `#[default]` for itself.
+    fn parse_default(field: &'a syn::Field) -> Result<Option<FieldDefault>, Error> {
+        field
+            .attrs
+            .iter()
+            .filter(|a| a.path.is_ident("default") || a.path.is_ident("validation_default"))
+            .map(|a| syn::parse2::<FieldDefault>(a.tokens.clone()))
+            .next_back()
+            .transpose()
+    }
+
+    /// Collects the sanitizers of all `#[sanitizer(...)]` attributes, in order of appearance
+    ///
+    /// Sanitizers are used as is and should be callable as `fn(T) -> T`, the built-in ones have
+    /// to be in scope like any other function.
+    fn parse_sanitizers(field: &'a syn::Field) -> Result<Vec<syn::Expr>, Error> {
+        let mut sanitizers = Vec::new();
+        for attr in field.attrs.iter().filter(|a| a.path.is_ident("sanitizer")) {
+            let exprs = attr.parse_args_with(
+                syn::punctuated::Punctuated::<syn::Expr, Token![,]>::parse_terminated,
+            )?;
+            sanitizers.extend(exprs);
+        }
+        Ok(sanitizers)
+    }
+
+    /// Name of the field
+    pub fn get_ident(&self) -> &syn::Ident {
+        self.name
     }
 
     /// Name of the field as token stream
@@ -41,9 +207,92 @@ impl<'a> ValidatedFieldDeriv<'a> {
         quote!(#name)
     }
 
-    /// True when the field has a validator attached
+    /// Declared type of the field
+    pub fn get_ty(&self) -> &syn::Type {
+        self.ty
+    }
+
+    /// Type of the field before validation
+    ///
+    /// This is the declared type, unless the validator changes it, e.g.
+    /// `#[validator(parse_email, from = String)]`
+    pub fn get_unvalidated_ty(&self) -> &syn::Type {
+        match &self.field_validator {
+            Some(FieldValidator {
+                from: Some(from), ..
+            }) => from,
+            _ => self.ty,
+        }
+    }
+
+    /// True when the field has a validator attached, in any group
     pub fn is_validated(&self) -> bool {
-        self.field_validator.is_some()
+        self.field_validator.is_some() || !self.group_validators.is_empty()
+    }
+
+    /// The `when` condition of the validator of the field, if there is one
+    pub fn when_condition(&self) -> Option<&syn::Expr> {
+        self.field_validator.as_ref()?.when.as_ref()
+    }
+
+    /// True when the field has validators that only apply to some groups
+    pub fn has_groups(&self) -> bool {
+        !self.group_validators.is_empty()
+    }
+
+    /// True when the field has validators with `severity = warn`
+    pub fn has_warnings(&self) -> bool {
+        !self.warn_validators.is_empty()
+    }
+
+    /// Emits code running the `severity = warn` validators against the validated value of the
+    /// field, bound to its name
+    ///
+    /// Warning validators take the value by reference, failures are pushed to `__warnings`. The
+    /// context, when required, is expected to be bound to `__ctx`, as `ctx` may be a field.
+    pub fn build_warnings_push(&self) -> TokenStream {
+        let name = self.name;
+        let field = name.to_string();
+        let capture = self.capture_fn();
+        let pushes = self.warn_validators.iter().map(|v| {
+            let func = &v.func;
+            let ctx = v.with_context.then(|| quote!(__ctx));
+            let push = |conversion| {
+                if v.capture_value {
+                    quote! {
+                        __warnings.push_with_value(
+                            #field,
+                            #conversion,
+                            ::std::option::Option::Some(#capture(&#name)),
+                        );
+                    }
+                } else {
+                    quote!(__warnings.push(#field, #conversion);)
+                }
+            };
+            match &v.error {
+                Some(error) => {
+                    let push = push(self.build_error_conversion(quote!(#error)));
+                    let ctx = ctx.map(|ctx| quote!(, #ctx));
+                    quote! {
+                        if !(#func)(&#name #ctx) {
+                            #push
+                        }
+                    }
+                }
+                None => {
+                    let push = push(self.build_error_conversion(quote!(w)));
+                    quote! {
+                        if let ::std::result::Result::Err(w) = (#func)(&#name, #ctx) {
+                            #push
+                        }
+                    }
+                }
+            }
+        });
+        quote! {
+            #( #pushes )*
+        }
     }
 
     /// Used to construct the validated instance from the unvalidated
@@ -51,39 +300,457 @@ impl<'a> ValidatedFieldDeriv<'a> {
     /// When there are no validators attached, its a simple field copy
     pub fn build_unvalidated_constructor(&self) -> TokenStream {
         let name = self.name;
+        let input = self.build_input();
         quote! {
-            #name: unvalidated.#name
+            #name: #input
         }
     }
 
+    /// Emits the value of the field taken from `unvalidated`, passed through its sanitizers
+    ///
+    /// Fields with a default are optional in the unvalidated struct, the default is filled in
+    /// before the sanitizers run.
+    pub fn build_input(&self) -> TokenStream {
+        let name = self.name;
+        let value = match &self.default {
+            Some(FieldDefault::Expr(default)) => quote! {
+                unvalidated.#name.unwrap_or_else(|| #default)
+            },
+            Some(FieldDefault::Default) => quote! {
+                unvalidated.#name.unwrap_or_default()
+            },
+            None => quote!(unvalidated.#name),
+        };
+        self.build_sanitized(value)
+    }
+
+    /// Emits `value` passed through the sanitizers of the field
+    fn build_sanitized(&self, value: TokenStream) -> TokenStream {
+        self.sanitizers.iter().fold(value, |input, sanitizer| {
+            quote! {
+                (#sanitizer)(#input)
+            }
+        })
+    }
+
     /// Emits code to execute the validator attached to field, if any
     ///
     /// The emitted code should yield a value of the type Result<T, E>
     /// where [T][ValidatedFieldDeriv.ty] is the type of the current field and E is the error type
-    /// of the current field
+    /// of the current field. Validators requiring a context are passed `ctx` as their second
+    /// argument.
+    ///
+    /// Validators with groups are not run here, fields having only those yield `Ok` as is.
     pub fn build_match_validator_call(&self) -> TokenStream {
-        let field = self.name;
-        let validator = &self.field_validator;
+        let input = self.build_input();
+        match &self.field_validator {
+            Some(FieldValidator { is_async: true, .. }) => {
+                let binding = self.async_binding();
+                quote!(#binding)
+            }
+            Some(v @ FieldValidator { when: Some(_), .. }) => {
+                let binding = self.when_binding();
+                let validation = self.build_validation(v, input.clone());
+                quote! {
+                    if #binding {
+                        #validation
+                    } else {
+                        ::std::result::Result::Ok(#input)
+                    }
+                }
+            }
+            Some(v) => self.build_validation(v, input),
+            None if self.has_groups() => {
+                let err = &self.custom_validation_error_ty;
+                quote!(::std::result::Result::<_, #err>::Ok(#input))
+            }
+            None => input,
+        }
+    }
+
+    /// Name of the local holding whether the `when` condition of the validator holds
+    fn when_binding(&self) -> syn::Ident {
+        format_ident!("__when_{}", self.name)
+    }
+
+    /// Evaluates the `when` condition of the validator against `unvalidated`, if there is one
+    ///
+    /// This has to be emitted before any field is moved out of `unvalidated`, the result is
+    /// picked up by [build_match_validator_call][Self::build_match_validator_call].
+    pub fn build_condition(&self) -> TokenStream {
+        match &self.field_validator {
+            Some(FieldValidator {
+                when: Some(when), ..
+            }) => {
+                let binding = self.when_binding();
+                quote! {
+                    let #binding: bool = (#when)(&unvalidated);
+                }
+            }
+            _ => quote!(),
+        }
+    }
+
+    /// True when the errors of the field capture the rejected value
+    ///
+    /// This is the case when any of its validators, except for the warning ones, has
+    /// `capture_value`.
+    pub fn captures_value(&self) -> bool {
+        self.field_validator
+            .iter()
+            .chain(&self.group_validators)
+            .any(|v| v.capture_value)
+    }
+
+    /// Name of the local holding the rendering of the value captured for the errors of the field
+    fn value_binding(&self) -> syn::Ident {
+        format_ident!("__value_{}", self.name)
+    }
+
+    /// Function rendering a reference to the value of the field, redacted for sensitive fields
+    fn capture_fn(&self) -> TokenStream {
+        if self.sensitive {
+            quote!(::valibuk::error::redact_value)
+        } else {
+            quote!(::valibuk::error::capture_value)
+        }
+    }
+
+    /// Captures the value of the field in `source`, the unvalidated struct, if its errors capture
+    /// the value
+    ///
+    /// Like [build_condition][Self::build_condition] this has to be emitted before any field is
+    /// moved out of `source`. Unset fields with a default capture nothing.
+    pub fn build_value_capture(&self, source: TokenStream) -> TokenStream {
+        if !self.captures_value() {
+            return quote!();
+        }
+        let name = self.name;
+        let binding = self.value_binding();
+        let capture = self.capture_fn();
+        let value = if self.default.is_some() {
+            quote!(#source.#name.as_ref().map(#capture))
+        } else {
+            quote!(::std::option::Option::Some(#capture(&#source.#name)))
+        };
+        quote! {
+            let #binding: ::std::option::Option<::std::string::String> = #value;
+        }
+    }
+
+    /// Captures the value of the field in `patch`, if its errors capture the value
+    pub fn build_patch_value_capture(&self) -> TokenStream {
+        if !self.captures_value() {
+            return quote!();
+        }
+        let name = self.name;
+        let binding = self.value_binding();
+        let capture = self.capture_fn();
+        quote! {
+            let #binding: ::std::option::Option<::std::string::String> =
+                patch.#name.as_ref().map(#capture);
+        }
+    }
+
+    /// Emits the same as [build_match_validator_call][Self::build_match_validator_call],
+    /// followed by the validators of the groups the group `__Group` is part of
+    ///
+    /// Group validators run in order of appearance, after the validator without groups, and stop
+    /// at the first error.
+    pub fn build_group_validator_call(&self) -> TokenStream {
+        let call = self.build_match_validator_call();
+        if !self.has_groups() {
+            return call;
+        }
+        let validations = self.group_validators.iter().map(|v| {
+            let groups = &v.groups;
+            let validation = self.build_validation(v, quote!(value));
+            quote! {
+                let value = if #( ::valibuk::groups::is_group::<__Group, #groups>() )||* {
+                    (#validation)?
+                } else {
+                    value
+                };
+            }
+        });
+        let ty = self.ty;
+        let err = &self.custom_validation_error_ty;
+        quote! {
+            ::std::result::Result::map_err(#call, ::std::convert::From::from).and_then(
+                |value| -> ::std::result::Result<#ty, #err> {
+                    #( #validations )*
+                    ::std::result::Result::Ok(value)
+                },
+            )
+        }
+    }
+
+    /// True when the field has an async validator attached
+    pub fn is_async(&self) -> bool {
+        matches!(
+            self.field_validator,
+            Some(FieldValidator { is_async: true, .. })
+        )
+    }
+
+    /// Name of the local holding the awaited result of the async validator of this field
+    pub fn async_binding(&self) -> syn::Ident {
+        format_ident!("__async_{}", self.name)
+    }
+
+    /// Emits the call to the async validator attached to this field, if any
+    ///
+    /// The emitted code yields a future of `Result<T, E>`, which is awaited together with the
+    /// futures of all the other async fields before the validated fields are matched.
+    pub fn build_async_validator_call(&self) -> Option<TokenStream> {
+        match &self.field_validator {
+            Some(v) if v.is_async => Some(self.build_validation(v, self.build_input())),
+            _ => None,
+        }
+    }
+
+    /// Runs the validator against `input`, passing the context when it requires one
+    ///
+    /// Predicates are turned into a `Result` here, using the error expression of the validator
+    fn build_validation(&self, validator: &FieldValidator, input: TokenStream) -> TokenStream {
+        let func = &validator.func;
+        let ctx = validator.with_context.then(|| quote!(, ctx));
         match validator {
-            crate::field::FieldValidator::Ident(v) => quote! {
-                (#v)(unvalidated.#field)
+            FieldValidator { by_ref: true, .. } => {
+                let check = self.build_ref_validation(validator, quote!(&value));
+                quote! {{
+                    let value = #input;
+                    (#check).map(|()| value)
+                }}
+            }
+            FieldValidator {
+                error: Some(error), ..
+            } => quote! {{
+                let value = #input;
+                if (#func)(&value #ctx) {
+                    ::std::result::Result::Ok(value)
+                } else {
+                    ::std::result::Result::Err(#error)
+                }
+            }},
+            FieldValidator {
+                with_context: true, ..
+            } => quote! {
+                (#func)(#input, ctx)
+            },
+            _ => quote! {
+                (#func)(#input)
+            },
+        }
+    }
+
+    /// Runs a validator taking the value by reference against `value`, a reference to the value
+    ///
+    /// The emitted code yields a `Result<(), E>`.
+    fn build_ref_validation(&self, validator: &FieldValidator, value: TokenStream) -> TokenStream {
+        let func = &validator.func;
+        let ctx = validator.with_context.then(|| quote!(, ctx));
+        match validator {
+            FieldValidator {
+                error: Some(error), ..
+            } => quote! {
+                if (#func)(#value #ctx) {
+                    ::std::result::Result::Ok(())
+                } else {
+                    ::std::result::Result::Err(#error)
+                }
             },
-            crate::field::FieldValidator::Closure(v) => quote! {
-                (#v)(unvalidated.#field)
+            FieldValidator {
+                with_context: true, ..
+            } => quote! {
+                (#func)(#value, ctx)
             },
-            crate::field::FieldValidator::None => quote! {
-                unvalidated.#field
+            _ => quote! {
+                (#func)(#value)
             },
         }
     }
 
+    /// True when the field has a validator taking the value by reference
+    pub fn is_checkable(&self) -> bool {
+        matches!(
+            self.field_validator,
+            Some(FieldValidator { by_ref: true, .. })
+        )
+    }
+
+    /// Emits code running the validator taking the value by reference against the field of
+    /// the unvalidated struct in `self`, without moving it
+    ///
+    /// Fields with a default are checked with the default when they are not set. Sanitizers need
+    /// to own the value, so they are not applied. The emitted code yields a `Result<(), E>`.
+    pub fn build_check(&self) -> Option<TokenStream> {
+        let name = self.name;
+        let validator = self.field_validator.as_ref().filter(|v| v.by_ref)?;
+        let check = match &self.default {
+            Some(default) => {
+                let default = match default {
+                    FieldDefault::Expr(default) => quote!(#default),
+                    FieldDefault::Default => quote!(::std::default::Default::default()),
+                };
+                let set = self.build_ref_validation(validator, quote!(value));
+                let unset = self.build_ref_validation(validator, quote!(&(#default)));
+                quote! {
+                    match &self.#name {
+                        ::std::option::Option::Some(value) => #set,
+                        ::std::option::Option::None => #unset,
+                    }
+                }
+            }
+            None => self.build_ref_validation(validator, quote!(&self.#name)),
+        };
+        Some(match &validator.when {
+            Some(when) => quote! {
+                if (#when)(self) {
+                    #check
+                } else {
+                    ::std::result::Result::Ok(())
+                }
+            },
+            None => check,
+        })
+    }
+
+    /// Builds the field of the patch struct, `None` keeps the current value
+    pub fn build_patch_struct_repr(&self) -> TokenStream {
+        let name = self.name;
+        let ty = self.get_unvalidated_ty();
+        quote! {
+            pub #name: ::std::option::Option<#ty>
+        }
+    }
+
+    /// Emits code validating the value of the field in `patch`, if there is one
+    ///
+    /// Yields the same as [build_match_validator_call][Self::build_match_validator_call], with
+    /// the current value of the field in `self` when it is not patched. Conditional validators
+    /// are rejected with `patch`, as there is no unvalidated struct to evaluate `when` against.
+    pub fn build_patch_validator_call(&self) -> TokenStream {
+        let name = self.name;
+        let input = self.build_sanitized(quote!(value));
+        match &self.field_validator {
+            Some(v) => {
+                let validation = self.build_validation(v, input);
+                let validation = if v.is_async {
+                    quote!(#validation.await)
+                } else {
+                    validation
+                };
+                quote! {
+                    match patch.#name {
+                        ::std::option::Option::Some(value) => #validation,
+                        ::std::option::Option::None => ::std::result::Result::Ok(self.#name),
+                    }
+                }
+            }
+            None if self.has_groups() => {
+                let err = &self.custom_validation_error_ty;
+                quote! {
+                    ::std::result::Result::<_, #err>::Ok(match patch.#name {
+                        ::std::option::Option::Some(value) => #input,
+                        ::std::option::Option::None => self.#name,
+                    })
+                }
+            }
+            None => quote! {
+                match patch.#name {
+                    ::std::option::Option::Some(value) => #input,
+                    ::std::option::Option::None => self.#name,
+                }
+            },
+        }
+    }
+
+    /// Fails unless the field is private
+    ///
+    /// Used for sealed structs, whose instances must only be created through validation
+    pub fn check_private(&self) -> Result<(), Error> {
+        match self.vis {
+            syn::Visibility::Inherited => Ok(()),
+            vis => Err(Error::new(
+                vis.span(),
+                "fields of a sealed struct must be private, use the generated getter instead",
+            )),
+        }
+    }
+
+    /// Builds a getter returning a reference to the field of the validated struct
+    pub fn build_getter(&self, vis: &syn::Visibility) -> TokenStream {
+        let name = self.name;
+        let ty = self.ty;
+        quote! {
+            #vis fn #name(&self) -> &#ty {
+                &self.#name
+            }
+        }
+    }
+
+    /// Builds `try_set_<field>` for the validated struct
+    ///
+    /// The setter sanitizes and validates only the new value and leaves the struct untouched
+    /// when it is rejected. It takes the context and is async when the validator requires it.
+    /// Like patches, setters are rejected on structs with conditional validators.
+    pub fn build_try_setter(&self, vis: &syn::Visibility) -> TokenStream {
+        let name = self.name;
+        let setter = format_ident!("try_set_{}", name);
+        let ty = self.get_unvalidated_ty();
+        let err = &self.custom_validation_error_ty;
+        let err = if self.is_nested() {
+            quote!(::valibuk::error::ValidationErrors<#err>)
+        } else {
+            quote!(#err)
+        };
+        let input = self.build_sanitized(quote!(value));
+        let assertions = self.build_field_assertions();
+        let nested = self.build_nested_errors(quote!(::std::convert::From::from));
+        let (asyncness, ctx, value) = match &self.field_validator {
+            Some(v) => {
+                let validation = self.build_validation(v, input);
+                let validation = match (v.nested, v.is_async) {
+                    (true, true) => quote! {
+                        ::std::result::Result::map_err(#validation.await, |e| #nested)
+                    },
+                    (true, false) => quote! {
+                        ::std::result::Result::map_err(#validation, |e| #nested)
+                    },
+                    (false, true) => quote!(#validation.await),
+                    (false, false) => validation,
+                };
+                let asyncness = v.is_async.then(|| quote!(async));
+                let ctx = if v.with_context {
+                    let ctx_ty = &self.context_ty;
+                    quote!(ctx: &#ctx_ty,)
+                } else {
+                    quote!()
+                };
+                (asyncness, ctx, quote!(#validation?))
+            }
+            None => (None, quote!(), input),
+        };
+        quote! {
+            #vis #asyncness fn #setter(
+                &mut self,
+                value: #ty,
+                #ctx
+            ) -> ::core::result::Result<(), #err> {
+                #assertions
+                self.#name = #value;
+                ::core::result::Result::Ok(())
+            }
+        }
+    }
+
     /// Builds the PatExpr that matches when the validator was successful
     ///
     /// This is used in the match expr to collect all the validated fields
     pub fn build_match_validator_ok(&self) -> TokenStream {
         let name = self.name;
-        let validator = &self.field_validator;
-        if validator.is_some() {
+        if self.is_validated() {
             quote! {
                 ::std::result::Result::Ok(#name)
             }
@@ -94,14 +761,58 @@ impl<'a> ValidatedFieldDeriv<'a> {
         }
     }
 
+    /// Builds the PatExpr binding the validator results when any of them failed
+    ///
+    /// Fields without a validator cannot fail, so their value is ignored
+    pub fn build_match_validator_nok(&self) -> TokenStream {
+        if self.is_validated() {
+            self.get_name()
+        } else {
+            quote!(_)
+        }
+    }
+
+    /// Builds the field of the partial struct, `None` when the field failed to validate
+    pub fn build_partial_struct_repr(&self) -> TokenStream {
+        let name = self.name;
+        let ty = self.ty;
+        quote! {
+            pub #name: ::std::option::Option<#ty>
+        }
+    }
+
+    /// Moves the result bound to the name of the field into the partial struct
+    ///
+    /// Used in the match arm binding all the validator results when any of them failed, the
+    /// error of the field, if any, is pushed to `errors`.
+    pub fn build_partial_field(&self) -> TokenStream {
+        let name = self.name;
+        let push = self.build_error_push();
+        if self.is_validated() {
+            quote! {
+                #name: match #name {
+                    ::std::result::Result::Ok(value) => ::std::option::Option::Some(value),
+                    ::std::result::Result::Err(e) => {
+                        #push
+                        ::std::option::Option::None
+                    }
+                }
+            }
+        } else {
+            quote! {
+                #name: ::std::option::Option::Some(#name)
+            }
+        }
+    }
+
     /// Builds error handling for when the validator fails
     pub fn build_validator_error_push(&self) -> TokenStream {
         let name = self.name;
-        let validator = &self.field_validator;
-        if validator.is_some() {
+        let push = self.build_error_push();
+        if self.is_validated() {
             quote! {
                 if let ::std::result::Result::Err(e) = #name {
-                    errors.push(e);
+                    #push
                 }
             }
         } else {
@@ -109,59 +820,490 @@ impl<'a> ValidatedFieldDeriv<'a> {
         }
     }
 
-    /// Emits dummy code that fails to compile when the declared
-    /// type of the custom error does not match the signature of
-    /// the validator for this field.
+    /// Pushes the error of the field bound to `e` to `errors`
+    ///
+    /// Nested validators fail with the errors of the nested value, they are reported under the
+    /// name of the field. The captured value is expected in its [binding][Self::value_binding].
+    pub fn build_error_push(&self) -> TokenStream {
+        let field = self.name.to_string();
+        let conversion = self.build_error_conversion(quote!(e));
+        if self.is_nested() {
+            let nested = self.build_nested_errors(quote!(|e| #conversion));
+            quote! {
+                errors.nest(#field, #nested);
+            }
+        } else if self.captures_value() {
+            let binding = self.value_binding();
+            quote! {
+                errors.push_with_value(#field, #conversion, #binding);
+            }
+        } else {
+            quote! {
+                errors.push(#field, #conversion);
+            }
+        }
+    }
+
+    /// Converts the nested errors `e` with `conversion`, redacting their captured values when
+    /// the field is marked with `#[sensitive]`
+    fn build_nested_errors(&self, conversion: TokenStream) -> TokenStream {
+        let errors = quote!(::valibuk::error::ValidationErrors::map(e, #conversion));
+        if self.sensitive {
+            quote!(::valibuk::error::ValidationErrors::redacted(#errors))
+        } else {
+            errors
+        }
+    }
+
+    /// True when the validator of the field fails with the errors of a nested value
+    pub fn is_nested(&self) -> bool {
+        matches!(
+            self.field_validator,
+            Some(FieldValidator { nested: true, .. })
+        )
+    }
+
+    /// Converts the error `e` of a validator of this field into the error type of the struct
+    ///
+    /// With a generated error enum, the error is converted into the error type of the field and
+    /// wrapped into the variant of the field.
+    pub fn build_error_conversion(&self, e: TokenStream) -> TokenStream {
+        match (&self.error_enum, self.error_variant()) {
+            (Some(error_enum), Some(variant)) => quote! {
+                #error_enum::#variant(::std::convert::From::from(#e))
+            },
+            _ => quote!(::std::convert::From::from(#e)),
+        }
+    }
+
+    /// Variant of the generated error enum holding the errors of this field, if it has any
+    pub fn error_variant(&self) -> Option<syn::Ident> {
+        (self.is_validated() || self.has_warnings())
+            .then(|| format_ident!("{}", to_camel_case(&self.name.to_string())))
+    }
+
+    /// Error type of the field, the one its validators errors are converted into
+    pub fn get_error_ty(&self) -> &syn::Type {
+        &self.custom_validation_error_ty
+    }
+
+    /// Error type declared with `error_type = T` on any of the validators of the field
+    pub fn declared_error_ty(&self) -> Option<&syn::Type> {
+        self.field_validator
+            .iter()
+            .chain(&self.group_validators)
+            .chain(&self.warn_validators)
+            .find_map(|v| v.error_type.as_ref())
+    }
+
+    /// Makes the errors of this field variants of the generated error enum `error_enum`
+    ///
+    /// The error type of the field becomes the one declared with `error_type = T`, or `String`.
+    pub fn use_error_enum(&mut self, error_enum: &syn::Ident) {
+        self.custom_validation_error_ty = self
+            .declared_error_ty()
+            .cloned()
+            .unwrap_or_else(|| syn::parse_quote!(::std::string::String));
+        self.error_enum = Some(error_enum.clone());
+    }
+
+    /// Emits dummy code that fails to compile when the signature of the validator does not match
+    /// the field, or when the custom error type cannot be converted from the error of the
+    /// validator with `From`.
     pub fn build_field_assertions(&self) -> TokenStream {
+        let assertions = self
+            .field_validator
+            .iter()
+            .chain(&self.group_validators)
+            .chain(&self.warn_validators)
+            .map(|v| self.build_validator_assertion(v));
+        quote! {
+            #( #assertions )*
+        }
+    }
+
+    fn build_validator_assertion(&self, validator: &FieldValidator) -> TokenStream {
         let ty = self.ty;
+        let input_ty = validator.from.as_ref().unwrap_or(self.ty);
         let err = &self.custom_validation_error_ty;
-        let validator = &self.field_validator;
+        let func = &validator.func;
+        let ctx = &self.context_ty;
+        let (ctx_param, ctx_arg) = if validator.with_context {
+            (quote!(ctx: &#ctx), quote!(ctx))
+        } else {
+            (quote!(), quote!())
+        };
         match validator {
-            FieldValidator::Ident(v) => quote! {
-                let _: fn(#ty) -> ::std::result::Result<#ty, #err> = #v;
+            FieldValidator { error: Some(_), .. } => quote!(),
+            _ if validator.by_ref || validator.severity == Severity::Warn => {
+                quote_spanned! {func.span()=>
+                    #[allow(clippy::redundant_closure_call)]
+                    let _ = |value: &#ty, #ctx_param| -> ::std::result::Result<(), #err> {
+                        ::std::result::Result::map_err((#func)(value, #ctx_arg), ::std::convert::From::from)
+                    };
+                }
+            }
+            // the output of async validators is checked when it is awaited
+            FieldValidator { is_async: true, .. } => quote!(),
+            FieldValidator { nested: true, .. } => quote_spanned! {func.span()=>
+                #[allow(clippy::redundant_closure_call)]
+                let _ = |value: #input_ty, #ctx_param| -> ::std::result::Result<#ty, ::valibuk::error::ValidationErrors<#err>> {
+                    ::std::result::Result::map_err((#func)(value, #ctx_arg), |e| {
+                        ::valibuk::error::ValidationErrors::map(e, ::std::convert::From::from)
+                    })
+                };
             },
-            FieldValidator::Closure(v) => quote! {
-                let _: fn(#ty) -> ::std::result::Result<#ty, #err> = #v;
+            _ => quote_spanned! {func.span()=>
+                #[allow(clippy::redundant_closure_call)]
+                let _ = |value: #input_ty, #ctx_param| -> ::std::result::Result<#ty, #err> {
+                    ::std::result::Result::map_err((#func)(value, #ctx_arg), ::std::convert::From::from)
+                };
             },
-            FieldValidator::None => quote!(),
         }
     }
 
     /// Builds fields for the unvalidated struct
+    ///
+    /// Fields with a default can be left out, so they are wrapped in an `Option`
     pub fn build_unvalidated_struct_repr(&self) -> TokenStream {
         let name = self.name;
-        let ty = self.ty;
+        let ty = self.build_unvalidated_ty();
         quote! {
             pub #name: #ty
         }
     }
+
+    /// Type of the field in the unvalidated struct
+    pub fn build_unvalidated_ty(&self) -> TokenStream {
+        let ty = self.get_unvalidated_ty();
+        if self.default.is_some() {
+            quote!(::std::option::Option<#ty>)
+        } else {
+            quote!(#ty)
+        }
+    }
+
+    /// Builds the field of the unvalidated struct builder, holding the value once it is set
+    pub fn build_builder_field(&self) -> TokenStream {
+        let name = self.name;
+        let ty = self.get_unvalidated_ty();
+        quote! {
+            #name: ::std::option::Option<#ty>
+        }
+    }
+
+    /// Builds the setter of the unvalidated struct builder, taking anything convertible into
+    /// the type of the field, e.g. `&str` for a `String`
+    pub fn build_builder_setter(&self, vis: &syn::Visibility) -> TokenStream {
+        let name = self.name;
+        let ty = self.get_unvalidated_ty();
+        quote! {
+            #vis fn #name(mut self, #name: impl ::std::convert::Into<#ty>) -> Self {
+                self.#name = ::std::option::Option::Some(::std::convert::Into::into(#name));
+                self
+            }
+        }
+    }
+
+    /// True when the validated value can be turned back into the unvalidated one
+    ///
+    /// Validators changing the type of the field need an `into` mapping for that
+    pub fn is_reversible(&self) -> bool {
+        !matches!(
+            self.field_validator,
+            Some(FieldValidator {
+                from: Some(_),
+                into: None,
+                ..
+            })
+        )
+    }
+
+    /// Moves the value of the field from `validated` back into the unvalidated struct
+    ///
+    /// The `into` mapping of the validator is applied if there is one
+    pub fn build_unvalidated_from_validated(&self) -> TokenStream {
+        let name = self.name;
+        let value = match &self.field_validator {
+            Some(FieldValidator {
+                into: Some(into), ..
+            }) => quote!((#into)(validated.#name)),
+            _ => quote!(validated.#name),
+        };
+        if self.default.is_some() {
+            quote! {
+                #name: ::std::option::Option::Some(#value)
+            }
+        } else {
+            quote! {
+                #name: #value
+            }
+        }
+    }
+
+    /// Type parameter tracking whether the field is set on the typestate builder
+    ///
+    /// Fields with a default are optional, so they are not tracked
+    pub fn typestate_param(&self) -> Option<syn::Ident> {
+        if self.default.is_some() {
+            None
+        } else {
+            Some(format_ident!("__{}", to_camel_case(&self.name.to_string())))
+        }
+    }
+
+    /// Builds the field of the typestate builder
+    pub fn build_typestate_builder_field(&self) -> TokenStream {
+        let name = self.name;
+        match self.typestate_param() {
+            Some(state) => quote!(#name: #state),
+            None => self.build_builder_field(),
+        }
+    }
+
+    /// Initial value of the field of a fresh typestate builder
+    pub fn build_typestate_builder_initial(&self) -> TokenStream {
+        let name = self.name;
+        if self.typestate_param().is_some() {
+            quote!(#name: ::valibuk::builder::Unset)
+        } else {
+            quote!(#name: ::std::option::Option::None)
+        }
+    }
+
+    /// Stores the value passed to the setter of the typestate builder
+    pub fn build_typestate_builder_set(&self) -> TokenStream {
+        let name = self.name;
+        if self.typestate_param().is_some() {
+            quote!(#name: ::valibuk::builder::Set(::std::convert::Into::into(#name)))
+        } else {
+            quote!(#name: ::std::option::Option::Some(::std::convert::Into::into(#name)))
+        }
+    }
+
+    /// Moves the value from the typestate builder into the unvalidated struct
+    pub fn build_typestate_builder_build(&self) -> TokenStream {
+        let name = self.name;
+        if self.typestate_param().is_some() {
+            quote!(#name: self.#name.0)
+        } else {
+            quote!(#name: self.#name)
+        }
+    }
+
+    /// Moves the value from the builder into the unvalidated struct
+    ///
+    /// Fields without a default are required, the builder fails when they are not set
+    pub fn build_builder_build(&self) -> TokenStream {
+        let name = self.name;
+        if self.default.is_some() {
+            quote! {
+                #name: self.#name
+            }
+        } else {
+            let field = name.to_string();
+            quote! {
+                #name: self.#name.ok_or(::valibuk::builder::MissingField(#field))?
+            }
+        }
+    }
 }
 
+/// Converts a snake_case field name to CamelCase
+fn to_camel_case(name: &str) -> String {
+    name.trim_start_matches("r#")
+        .split('_')
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let mut chars = part.chars();
+            match chars.next() {
+                Some(first) => first.to_uppercase().chain(chars).collect(),
+                None => String::new(),
+            }
+        })
+        .collect()
+}
+
+/// Parsed `#[default]`, `#[default = literal]` or `#[default(expr)]` field attribute, or the same
+/// forms of `#[validation_default]`
 #[derive(Debug, PartialEq)]
-pub enum FieldValidator {
-    Ident(syn::Ident),
-    Closure(syn::ExprClosure),
-    None,
+pub enum FieldDefault {
+    /// `#[default]`, fills in `Default::default()`
+    Default,
+    /// `#[default = literal]` or `#[default(expr)]`, fills in the result of the expression
+    ///
+    /// String literals are converted with `Into`, so they can be used for `String` fields
+    Expr(Box<syn::Expr>),
 }
 
-impl FieldValidator {
-    pub fn is_some(&self) -> bool {
-        self != &FieldValidator::None
+impl Parse for FieldDefault {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        if input.is_empty() {
+            return Ok(FieldDefault::Default);
+        }
+        if input.peek(syn::token::Paren) {
+            let content;
+            syn::parenthesized!(content in input);
+            return Ok(FieldDefault::Expr(content.parse()?));
+        }
+        input.parse::<Token![=]>()?;
+        let lit: syn::Lit = input.parse()?;
+        Ok(FieldDefault::Expr(Box::new(match lit {
+            syn::Lit::Str(s) => syn::parse_quote!(::std::convert::Into::into(#s)),
+            lit => syn::parse_quote!(#lit),
+        })))
     }
 }
 
-impl From<&syn::Attribute> for FieldValidator {
-    fn from(value: &syn::Attribute) -> Self {
-        let ident = value
-            .parse_args::<syn::Ident>()
-            .map(|i| FieldValidator::Ident(i));
-        let closure = value
-            .parse_args::<syn::ExprClosure>()
-            .map(|i| FieldValidator::Closure(i));
-        ident.or(closure).unwrap_or(FieldValidator::None)
+/// Parsed contents of the `#[validator(...)]` field attribute
+///
+/// The first argument is the validator itself, any expression callable as `fn(T) -> Result<T, E>`
+/// (a fn name, a path or a closure). It can be followed by:
+/// * an error expression, turning the validator into a `fn(&T) -> bool` predicate that yields
+///   this error when it returns false
+/// * `context`, marking the validator as `fn(T, &C) -> Result<T, E>` where `C` is the type set
+///   by `#[validation(context = C)]`, or predicates as `fn(&T, &C) -> bool`
+///
+/// * `from = U`, for validators changing the type, i.e. `fn(U) -> Result<T, E>`, the field then
+///   has the type `U` in the unvalidated struct
+/// * `into = f`, a `fn(T) -> U` mapping the validated value back to the unvalidated one
+/// * `groups(A, B)`, running the validator only when validating for one of these groups with
+///   `validate_for`, the groups are types implementing `valibuk::groups::Group`
+/// * `when = f`, a `fn(&UnvalidatedA) -> bool` deciding whether the validator runs at all, the
+///   value is taken as is when it does not
+/// * `severity = warn`, turning failures into warnings reported by `validate_with_warnings`
+///   instead of errors, such validators have the form of `fn(&T) -> Result<(), E>`
+///
+/// Prefixing the validator with `async` marks it as `async fn(T) -> Result<T, E>`, prefixing it
+/// with `ref` marks it as `fn(&T) -> Result<(), E>`, which can also be run by
+/// `UnvalidatedA::check(&self)`.
+#[derive(Debug, PartialEq)]
+pub struct FieldValidator {
+    pub func: syn::Expr,
+    pub error: Option<syn::Expr>,
+    pub with_context: bool,
+    pub is_async: bool,
+    pub by_ref: bool,
+    pub from: Option<syn::Type>,
+    pub into: Option<syn::Expr>,
+    pub groups: Vec<syn::Path>,
+    pub severity: Severity,
+    pub when: Option<syn::Expr>,
+    pub error_type: Option<syn::Type>,
+    pub nested: bool,
+    pub capture_value: bool,
+}
+
+/// What a failing validator results in
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum Severity {
+    /// The validation fails, this is the default
+    Error,
+    /// The validation succeeds, the failure is reported as a warning
+    Warn,
+}
+
+impl Parse for FieldValidator {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let is_async = input.parse::<Option<Token![async]>>()?.is_some();
+        let by_ref = input.parse::<Option<Token![ref]>>()?.is_some();
+        if is_async && by_ref {
+            return Err(input.error("async validators cannot take the value by reference"));
+        }
+        let func = input.parse()?;
+        let mut validator = FieldValidator {
+            func,
+            error: None,
+            with_context: false,
+            is_async,
+            by_ref,
+            from: None,
+            into: None,
+            groups: Vec::new(),
+            severity: Severity::Error,
+            when: None,
+            error_type: None,
+            nested: false,
+            capture_value: false,
+        };
+        while !input.is_empty() {
+            input.parse::<Token![,]>()?;
+            if input.is_empty() {
+                break;
+            }
+            match peek_option(input).as_deref() {
+                Some("context") => {
+                    input.parse::<syn::Ident>()?;
+                    validator.with_context = true;
+                }
+                Some("nested") => {
+                    input.parse::<syn::Ident>()?;
+                    validator.nested = true;
+                }
+                Some("capture_value") => {
+                    input.parse::<syn::Ident>()?;
+                    validator.capture_value = true;
+                }
+                Some("from") => {
+                    input.parse::<syn::Ident>()?;
+                    input.parse::<Token![=]>()?;
+                    validator.from = Some(input.parse()?);
+                }
+                Some("into") => {
+                    input.parse::<syn::Ident>()?;
+                    input.parse::<Token![=]>()?;
+                    validator.into = Some(input.parse()?);
+                }
+                Some("error_type") => {
+                    input.parse::<syn::Ident>()?;
+                    input.parse::<Token![=]>()?;
+                    validator.error_type = Some(input.parse()?);
+                }
+                Some("when") => {
+                    input.parse::<syn::Ident>()?;
+                    input.parse::<Token![=]>()?;
+                    validator.when = Some(input.parse()?);
+                }
+                Some("severity") => {
+                    input.parse::<syn::Ident>()?;
+                    input.parse::<Token![=]>()?;
+                    let severity: syn::Ident = input.parse()?;
+                    validator.severity = match severity.to_string().as_str() {
+                        "error" => Severity::Error,
+                        "warn" => Severity::Warn,
+                        _ => return Err(Error::new(severity.span(), "expected `error` or `warn`")),
+                    };
+                }
+                Some("groups") => {
+                    input.parse::<syn::Ident>()?;
+                    let content;
+                    syn::parenthesized!(content in input);
+                    let groups =
+                        content.parse_terminated::<syn::Path, Token![,]>(syn::Path::parse)?;
+                    validator.groups.extend(groups);
+                }
+                _ if validator.error.is_none() && !validator.is_async => {
+                    validator.error = Some(input.parse()?);
+                }
+                _ => return Err(input.error("unexpected validator argument")),
+            }
+        }
+        Ok(validator)
     }
 }
 
+/// Name of the option if the input continues with `name`, followed by a comma or the end of the
+/// input, with `name = ...`, or with `groups(...)`
+fn peek_option(input: ParseStream) -> Option<String> {
+    let fork = input.fork();
+    let name = fork.parse::<syn::Ident>().ok()?;
+    let is_option = fork.is_empty()
+        || fork.peek(Token![,])
+        || (fork.peek(Token![=]) && !fork.peek(Token![==]))
+        || (name == "groups" && fork.peek(syn::token::Paren));
+    is_option.then(|| name.to_string())
+}
+
 #[cfg(test)]
 mod test {
     use syn::parse_quote;
@@ -178,7 +1320,12 @@ mod test {
             },
             _ => unimplemented!(),
         };
-        ValidatedFieldDeriv::new(fields.named.iter().last().unwrap(), parse_quote!(String)).unwrap()
+        ValidatedFieldDeriv::new(
+            fields.named.iter().next_back().unwrap(),
+            parse_quote!(String),
+            None,
+        )
+        .unwrap()
     }
 
     #[test]
@@ -230,7 +1377,7 @@ mod test {
                 }
             };
             let f = first_field_deriv_from_struct(&s);
-            assert_eq!(f.is_validated(), false, "field a is not validated");
+            assert!(!f.is_validated(), "field a is not validated");
         }
         {
             let s: syn::DeriveInput = parse_quote! {
@@ -240,7 +1387,7 @@ mod test {
                 }
             };
             let f = first_field_deriv_from_struct(&s);
-            assert_eq!(f.is_validated(), true, "field a is validated");
+            assert!(f.is_validated(), "field a is validated");
         }
     }
 
@@ -265,17 +1412,40 @@ mod test {
             );
         }
         {
-            // inline fn validator case
+            // inline fn validator case
+            let s: syn::DeriveInput = parse_quote! {
+                struct A {
+                    #[validator(|a| if a > 0 { Ok(a) } else { Err("err") })]
+                    a: i32
+                }
+            };
+            let f = first_field_deriv_from_struct(&s);
+            let expected: syn::ExprCall = parse_quote! {
+                (|a| if a > 0 { Ok(a) } else { Err("err") })(unvalidated.a)
+            };
+            assert_tokens_eq!(
+                f.build_match_validator_call(),
+                &expected,
+                "validator call for fn validator"
+            );
+        }
+        {
+            // inline "bool fn validator, error string" case
             let s: syn::DeriveInput = parse_quote! {
                 struct A {
-                    #[validator(|a| if a > 0 { Ok(a) } else { Err("err") })]
+                    #[validator(|a: &i32| *a > 0, "Validation Err".to_string())]
                     a: i32
                 }
             };
             let f = first_field_deriv_from_struct(&s);
-            let expected: syn::ExprCall = parse_quote! {
-                (|a| if a > 0 { Ok(a) } else { Err("err") })(unvalidated.a)
-            };
+            let expected: syn::ExprBlock = parse_quote! {{
+                let value = unvalidated.a;
+                if (|a: &i32| *a > 0)(&value) {
+                    ::std::result::Result::Ok(value)
+                } else {
+                    ::std::result::Result::Err("Validation Err".to_string())
+                }
+            }};
             assert_tokens_eq!(
                 f.build_match_validator_call(),
                 &expected,
@@ -283,25 +1453,21 @@ mod test {
             );
         }
         {
-            // inline "bool fn validator, error string" case
+            // validator named like a built-in one case
             let s: syn::DeriveInput = parse_quote! {
                 struct A {
-                    #[validator(|ref a| a > 0, "Validation Err".to_string())]
+                    #[validator(range(1, 10))]
                     a: i32
                 }
             };
             let f = first_field_deriv_from_struct(&s);
-            let expected: syn::Expr = parse_quote! {
-                if (|ref a | a > 0)(unvalidated.a) {
-                    Ok(unvalidated.a)
-                } else {
-                    Err("Validation Err".to_string())
-                }
+            let expected: syn::ExprCall = parse_quote! {
+                (range(1, 10))(unvalidated.a)
             };
             assert_tokens_eq!(
                 f.build_match_validator_call(),
                 &expected,
-                "validator call for fn validator"
+                "validator call for validator named like a built-in one"
             );
         }
         {
@@ -323,6 +1489,334 @@ mod test {
         }
     }
 
+    #[test]
+    fn test_builder() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let actual: syn::ImplItemMethod =
+            syn::parse2(f.build_builder_setter(&parse_quote!(pub))).expect("setter is a method");
+        let expected: syn::ImplItemMethod = parse_quote! {
+            pub fn a(mut self, a: impl ::std::convert::Into<i32>) -> Self {
+                self.a = ::std::option::Option::Some(::std::convert::Into::into(a));
+                self
+            }
+        };
+        assert_tokens_eq!(&actual, &expected, "builder setter");
+        let expected: syn::FieldValue = parse_quote! {
+            a: self.a.ok_or(::valibuk::builder::MissingField("a"))?
+        };
+        assert_tokens_eq!(f.build_builder_build(), &expected, "required field");
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[default]
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected: syn::FieldValue = parse_quote! {
+            a: self.a
+        };
+        assert_tokens_eq!(f.build_builder_build(), &expected, "field with default");
+    }
+
+    #[test]
+    fn test_build_try_setter() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[sanitizer(trim)]
+                #[validator(abc)]
+                a: String
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let actual: syn::ImplItemMethod =
+            syn::parse2(f.build_try_setter(&parse_quote!(pub))).expect("setter is a method");
+        let expected: syn::ImplItemMethod = parse_quote! {
+            pub fn try_set_a(&mut self, value: String,) -> ::core::result::Result<(), String> {
+                #[allow(clippy::redundant_closure_call)]
+                let _ = |value: String,| -> ::std::result::Result<String, String> {
+                    ::std::result::Result::map_err((abc)(value,), ::std::convert::From::from)
+                };
+                self.a = (abc)((trim)(value))?;
+                ::core::result::Result::Ok(())
+            }
+        };
+        assert_tokens_eq!(&actual, &expected, "setter validating the new value");
+    }
+
+    #[test]
+    fn test_build_patch_validator_call() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[validator(abc)]
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected: syn::ExprMatch = parse_quote! {
+            match patch.a {
+                ::std::option::Option::Some(value) => (abc)(value),
+                ::std::option::Option::None => ::std::result::Result::Ok(self.a),
+            }
+        };
+        assert_tokens_eq!(
+            f.build_patch_validator_call(),
+            &expected,
+            "only the patched value is validated"
+        );
+    }
+
+    #[test]
+    fn test_build_group_validator_call() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[validator(abc, groups(Create, Update))]
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        assert!(f.is_validated(), "field a is validated in some groups");
+        let expected: syn::ExprCall = parse_quote! {
+            ::std::result::Result::<_, String>::Ok(unvalidated.a)
+        };
+        assert_tokens_eq!(
+            f.build_match_validator_call(),
+            &expected,
+            "validators with groups are skipped"
+        );
+        let expected: syn::ExprMethodCall = parse_quote! {
+            ::std::result::Result::map_err(
+                ::std::result::Result::<_, String>::Ok(unvalidated.a),
+                ::std::convert::From::from
+            ).and_then(|value| -> ::std::result::Result<i32, String> {
+                let value = if ::valibuk::groups::is_group::<__Group, Create>()
+                    || ::valibuk::groups::is_group::<__Group, Update>()
+                {
+                    ((abc)(value))?
+                } else {
+                    value
+                };
+                ::std::result::Result::Ok(value)
+            },)
+        };
+        assert_tokens_eq!(
+            f.build_group_validator_call(),
+            &expected,
+            "validators of the group run"
+        );
+    }
+
+    #[test]
+    fn test_build_warnings_push() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[validator(abc, severity = warn)]
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        assert!(!f.is_validated(), "warnings cannot fail the validation");
+        assert!(f.has_warnings(), "field a has warnings");
+        let expected: syn::ExprIf = parse_quote! {
+            if let ::std::result::Result::Err(w) = (abc)(&a,) {
+                __warnings.push("a", ::std::convert::From::from(w));
+            }
+        };
+        assert_tokens_eq!(f.build_warnings_push(), &expected, "warning for field a");
+    }
+
+    #[test]
+    fn test_conditional_validator() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[validator(abc, when = |u: &UnvalidatedA| u.b)]
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected: syn::Stmt = parse_quote! {
+            let __when_a: bool = (|u: &UnvalidatedA| u.b)(&unvalidated);
+        };
+        assert_tokens_eq!(
+            f.build_condition(),
+            &expected,
+            "condition evaluated upfront"
+        );
+        let expected: syn::ExprIf = parse_quote! {
+            if __when_a {
+                (abc)(unvalidated.a)
+            } else {
+                ::std::result::Result::Ok(unvalidated.a)
+            }
+        };
+        assert_tokens_eq!(
+            f.build_match_validator_call(),
+            &expected,
+            "validator only runs when the condition holds"
+        );
+    }
+
+    #[test]
+    fn test_ref_validator() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[default]
+                #[validator(ref abc)]
+                a: String
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        assert!(f.is_checkable(), "field a can be checked by reference");
+        let expected: syn::ExprBlock = parse_quote! {{
+            let value = unvalidated.a.unwrap_or_default();
+            ((abc)(&value)).map(|()| value)
+        }};
+        assert_tokens_eq!(
+            f.build_match_validator_call(),
+            &expected,
+            "value is moved only once"
+        );
+        let expected: syn::ExprMatch = parse_quote! {
+            match &self.a {
+                ::std::option::Option::Some(value) => (abc)(value),
+                ::std::option::Option::None => (abc)(&(::std::default::Default::default())),
+            }
+        };
+        assert_tokens_eq!(
+            f.build_check().expect("ref validator"),
+            &expected,
+            "check borrows the value"
+        );
+    }
+
+    #[test]
+    fn test_sealed() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        assert!(f.check_private().is_ok(), "private field");
+        let actual: syn::ImplItemMethod =
+            syn::parse2(f.build_getter(&parse_quote!(pub))).expect("getter is a method");
+        let expected: syn::ImplItemMethod = parse_quote! {
+            pub fn a(&self) -> &i32 {
+                &self.a
+            }
+        };
+        assert_tokens_eq!(&actual, &expected, "getter");
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                pub(crate) a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        assert!(f.check_private().is_err(), "public field");
+    }
+
+    #[test]
+    fn test_to_camel_case() {
+        assert_eq!(to_camel_case("email"), "Email");
+        assert_eq!(to_camel_case("vat_id"), "VatId");
+        assert_eq!(to_camel_case("_b"), "B");
+        assert_eq!(to_camel_case("r#type"), "Type");
+    }
+
+    #[test]
+    fn test_default() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[default = 8080]
+                a: u16
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected: syn::Expr = parse_quote! {
+            unvalidated.a.unwrap_or_else(|| 8080)
+        };
+        assert_tokens_eq!(f.build_input(), &expected, "expr default");
+        let actual = f.build_unvalidated_struct_repr();
+        let actual: syn::ItemStruct = parse_quote! {
+            struct B {
+                #actual
+            }
+        };
+        let expected: syn::ItemStruct = parse_quote! {
+            struct B {
+                pub a: ::std::option::Option<u16>
+            }
+        };
+        assert_tokens_eq!(&actual, &expected, "optional unvalidated field");
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[default]
+                #[sanitizer(trim)]
+                a: String
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected: syn::Expr = parse_quote! {
+            (trim)(unvalidated.a.unwrap_or_default())
+        };
+        assert_tokens_eq!(f.build_input(), &expected, "default before sanitizers");
+    }
+
+    #[test]
+    fn test_build_input() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[sanitizer(trim, normalize)]
+                #[sanitizer(|s| s)]
+                #[validator(abc)]
+                a: String
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected: syn::ExprCall = parse_quote! {
+            (|s| s)((normalize)((trim)(unvalidated.a)))
+        };
+        assert_tokens_eq!(f.build_input(), &expected, "sanitizers applied in order");
+        let expected: syn::ExprCall = parse_quote! {
+            (abc)((|s| s)((normalize)((trim)(unvalidated.a))))
+        };
+        assert_tokens_eq!(
+            f.build_match_validator_call(),
+            &expected,
+            "validator takes the sanitized value"
+        );
+    }
+
+    #[test]
+    fn test_build_async_validator_call() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[validator(async abc)]
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        assert!(f.is_async(), "field a is validated asynchronously");
+        let expected: syn::ExprCall = parse_quote! {
+            (abc)(unvalidated.a)
+        };
+        assert_tokens_eq!(
+            f.build_async_validator_call().expect("async call"),
+            &expected,
+            "future for async validator"
+        );
+        let expected: syn::Ident = parse_quote!(__async_a);
+        assert_tokens_eq!(
+            f.build_match_validator_call(),
+            &expected,
+            "match takes the awaited result"
+        );
+    }
+
     #[test]
     fn test_build_match_validator_ok() {
         let s: syn::DeriveInput = parse_quote! {
@@ -371,7 +1865,7 @@ mod test {
         let f = first_field_deriv_from_struct(&s);
         let expected: syn::Expr = parse_quote! {
             if let ::std::result::Result::Err(e) = a {
-                errors.push(e);
+                errors.push("a", ::std::convert::From::from(e));
             }
         };
         assert_tokens_eq!(
@@ -379,6 +1873,99 @@ mod test {
             &expected,
             "if expr for validated"
         );
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[validator(B::try_from, from = UnvalidatedB, nested)]
+                a: B
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected: syn::Expr = parse_quote! {
+            if let ::std::result::Result::Err(e) = a {
+                errors.nest("a", ::valibuk::error::ValidationErrors::map(e, |e| ::std::convert::From::from(e)));
+            }
+        };
+        assert_tokens_eq!(
+            &f.build_validator_error_push(),
+            &expected,
+            "nested errors for nested validator"
+        );
+    }
+
+    #[test]
+    fn test_build_value_capture() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[validator(abc, capture_value)]
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected = quote! {
+            let __value_a: ::std::option::Option<::std::string::String> =
+                ::std::option::Option::Some(::valibuk::error::capture_value(&unvalidated.a));
+        };
+        assert_tokens_eq!(
+            &f.build_value_capture(quote!(unvalidated)),
+            &expected,
+            "value captured for the errors of a"
+        );
+        let expected = quote! {
+            errors.push_with_value("a", ::std::convert::From::from(e), __value_a);
+        };
+        assert_tokens_eq!(&f.build_error_push(), &expected, "captured value pushed");
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[sensitive]
+                #[validator(abc, capture_value)]
+                a: String
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected = quote! {
+            let __value_a: ::std::option::Option<::std::string::String> =
+                ::std::option::Option::Some(::valibuk::error::redact_value(&unvalidated.a));
+        };
+        assert_tokens_eq!(
+            &f.build_value_capture(quote!(unvalidated)),
+            &expected,
+            "sensitive values are redacted"
+        );
+    }
+
+    #[test]
+    fn test_build_partial_field() {
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                #[validator(abc)]
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected: syn::FieldValue = parse_quote! {
+            a: match a {
+                ::std::result::Result::Ok(value) => ::std::option::Option::Some(value),
+                ::std::result::Result::Err(e) => {
+                    errors.push("a", ::std::convert::From::from(e));
+                    ::std::option::Option::None
+                }
+            }
+        };
+        assert_tokens_eq!(f.build_partial_field(), &expected, "validated field");
+        let s: syn::DeriveInput = parse_quote! {
+            struct A {
+                a: i32
+            }
+        };
+        let f = first_field_deriv_from_struct(&s);
+        let expected: syn::FieldValue = parse_quote! {
+            a: ::std::option::Option::Some(a)
+        };
+        assert_tokens_eq!(
+            f.build_partial_field(),
+            &expected,
+            "field without validator"
+        );
     }
 
     #[test]
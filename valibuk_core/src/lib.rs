@@ -78,11 +78,17 @@ mod tests {
             }
             #[automatically_derived]
             impl ::std::convert::TryFrom<UnvalidatedA> for A {
-                type Error = ::std::vec::Vec<::std::string::String>;
+                type Error = ::valibuk::error::ValidationErrors<::std::string::String>;
                 fn try_from(unvalidated: UnvalidatedA) -> ::core::result::Result<Self, Self::Error> {
                     Ok(A { a: unvalidated.a })
                 }
             }
+            #[automatically_derived]
+            impl ::std::convert::From<A> for UnvalidatedA {
+                fn from(validated: A) -> Self {
+                    UnvalidatedA { a: validated.a, }
+                }
+            }
         };
         assert_tokens_eq!(&expected, &after);
     }
@@ -103,11 +109,17 @@ mod tests {
             }
             #[automatically_derived]
             impl ::std::convert::TryFrom<UnvalidatedA> for A {
-                type Error = ::std::vec::Vec<E>;
+                type Error = ::valibuk::error::ValidationErrors<E>;
                 fn try_from(unvalidated: UnvalidatedA) -> ::core::result::Result<Self, Self::Error> {
                     Ok(A { a: unvalidated.a })
                 }
             }
+            #[automatically_derived]
+            impl ::std::convert::From<A> for UnvalidatedA {
+                fn from(validated: A) -> Self {
+                    UnvalidatedA { a: validated.a, }
+                }
+            }
         };
         assert_tokens_eq!(&expected, &after);
     }
@@ -127,11 +139,171 @@ mod tests {
             }
             #[automatically_derived]
             impl<'a> ::std::convert::TryFrom<UnvalidatedA<'a>> for A<'a> {
-                type Error = ::std::vec::Vec<::std::string::String>;
+                type Error = ::valibuk::error::ValidationErrors<::std::string::String>;
                 fn try_from(unvalidated: UnvalidatedA<'a>) -> ::core::result::Result<Self, Self::Error> {
                     Ok(A { a: unvalidated.a })
                 }
             }
+            #[automatically_derived]
+            impl<'a> ::std::convert::From<A<'a> > for UnvalidatedA<'a> {
+                fn from(validated: A<'a>) -> Self {
+                    UnvalidatedA { a: validated.a, }
+                }
+            }
+        };
+        assert_tokens_eq!(&expected, &after);
+    }
+
+    #[test]
+    fn test_type_changing_validator() {
+        let before = quote! {
+            struct A {
+                #[validator(parse, from = String, into = to_string)]
+                a: Email
+            }
+        };
+        let after = valibuk_core(before);
+        let expected = quote! {
+            #[automatically_derived]
+            struct UnvalidatedA {
+                pub a: String,
+            }
+            #[automatically_derived]
+            impl ::std::convert::TryFrom<UnvalidatedA> for A {
+                type Error = ::valibuk::error::ValidationErrors<::std::string::String>;
+                fn try_from(unvalidated: UnvalidatedA) -> ::core::result::Result<Self, Self::Error> {
+                    #[allow(clippy::redundant_closure_call)]
+                    let _ = |value: String,| -> ::std::result::Result<Email, ::std::string::String> {
+                        ::std::result::Result::map_err((parse)(value,), ::std::convert::From::from)
+                    };
+                    match ((parse)(unvalidated.a),) {
+                        (::std::result::Result::Ok(a),) => ::std::result::Result::Ok(A { a, }),
+                        (a,) => {
+                            let mut errors: ::valibuk::error::ValidationErrors<::std::string::String> =
+                                ::valibuk::error::ValidationErrors::new();
+                            if let ::std::result::Result::Err(e) = a {
+                                errors.push("a", ::std::convert::From::from(e));
+                            }
+                            ::std::result::Result::Err(errors)
+                        }
+                    }
+                }
+            }
+            #[automatically_derived]
+            impl ::std::convert::From<A> for UnvalidatedA {
+                fn from(validated: A) -> Self {
+                    UnvalidatedA { a: (to_string)(validated.a), }
+                }
+            }
+        };
+        assert_tokens_eq!(&expected, &after);
+    }
+
+    #[test]
+    fn test_auto_validation_error() {
+        let before = quote! {
+            #[validation_error(auto)]
+            struct A {
+                #[validator(v, error_type = E)]
+                a: i32
+            }
+        };
+        let after = valibuk_core(before);
+        let expected = quote! {
+            #[automatically_derived]
+            #[derive(Debug)]
+            enum AValidationError {
+                A(E),
+            }
+            #[automatically_derived]
+            impl ::std::fmt::Display for AValidationError {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match *self {
+                        AValidationError::A(ref e) => ::std::fmt::Display::fmt(e, f),
+                    }
+                }
+            }
+            #[automatically_derived]
+            impl ::std::error::Error for AValidationError {}
+            #[automatically_derived]
+            struct UnvalidatedA {
+                pub a: i32,
+            }
+            #[automatically_derived]
+            impl ::std::convert::TryFrom<UnvalidatedA> for A {
+                type Error = ::valibuk::error::ValidationErrors<AValidationError>;
+                fn try_from(unvalidated: UnvalidatedA) -> ::core::result::Result<Self, Self::Error> {
+                    #[allow(clippy::redundant_closure_call)]
+                    let _ = |value: i32,| -> ::std::result::Result<i32, E> {
+                        ::std::result::Result::map_err((v)(value,), ::std::convert::From::from)
+                    };
+                    match ((v)(unvalidated.a),) {
+                        (::std::result::Result::Ok(a),) => ::std::result::Result::Ok(A { a, }),
+                        (a,) => {
+                            let mut errors: ::valibuk::error::ValidationErrors<AValidationError> =
+                                ::valibuk::error::ValidationErrors::new();
+                            if let ::std::result::Result::Err(e) = a {
+                                errors.push("a", AValidationError::A(::std::convert::From::from(e)));
+                            }
+                            ::std::result::Result::Err(errors)
+                        }
+                    }
+                }
+            }
+            #[automatically_derived]
+            impl ::std::convert::From<A> for UnvalidatedA {
+                fn from(validated: A) -> Self {
+                    UnvalidatedA { a: validated.a, }
+                }
+            }
+        };
+        assert_tokens_eq!(&expected, &after);
+    }
+
+    #[test]
+    fn test_context() {
+        let before = quote! {
+            #[validation(context = C)]
+            struct A {
+                #[validator(v, context)]
+                a: i32
+            }
+        };
+        let after = valibuk_core(before);
+        let expected = quote! {
+            #[automatically_derived]
+            struct UnvalidatedA {
+                pub a: i32,
+            }
+            #[automatically_derived]
+            impl A {
+                fn validate_with(
+                    unvalidated: UnvalidatedA,
+                    ctx: &C,
+                ) -> ::core::result::Result<Self, ::valibuk::error::ValidationErrors<::std::string::String>> {
+                    #[allow(clippy::redundant_closure_call)]
+                    let _ = |value: i32, ctx: &C| -> ::std::result::Result<i32, ::std::string::String> {
+                        ::std::result::Result::map_err((v)(value, ctx), ::std::convert::From::from)
+                    };
+                    match ((v)(unvalidated.a, ctx),) {
+                        (::std::result::Result::Ok(a),) => ::std::result::Result::Ok(A { a, }),
+                        (a,) => {
+                            let mut errors: ::valibuk::error::ValidationErrors<::std::string::String> =
+                                ::valibuk::error::ValidationErrors::new();
+                            if let ::std::result::Result::Err(e) = a {
+                                errors.push("a", ::std::convert::From::from(e));
+                            }
+                            ::std::result::Result::Err(errors)
+                        }
+                    }
+                }
+            }
+            #[automatically_derived]
+            impl ::std::convert::From<A> for UnvalidatedA {
+                fn from(validated: A) -> Self {
+                    UnvalidatedA { a: validated.a, }
+                }
+            }
         };
         assert_tokens_eq!(&expected, &after);
     }
@@ -1,6 +1,6 @@
 use proc_macro2::TokenStream;
-use quote::quote;
-use syn::{parse_quote, Attribute, Error, Token};
+use quote::{format_ident, quote};
+use syn::{parse::ParseStream, parse_quote, spanned::Spanned, Attribute, Error, Token};
 
 use crate::field::ValidatedFieldDeriv;
 
@@ -11,6 +11,9 @@ pub struct ValidatedDeriv<'a> {
     unvalidated_name: syn::Ident,
     generics: &'a syn::Generics,
     custom_validation_error_ty: syn::Type,
+    /// Name of the error enum generated for `#[validation_error(auto)]`
+    error_enum: Option<syn::Ident>,
+    options: ValidationOptions,
     fields: Vec<ValidatedFieldDeriv<'a>>,
 }
 
@@ -23,12 +26,61 @@ impl<'a> ValidatedDeriv<'a> {
             &format!("Unvalidated{}", ast.ident),
             proc_macro2::Span::call_site(),
         );
-        let custom_validation_error_ty: syn::Type = Self::validation_error_from_attrs(&ast.attrs);
-        // dbg!(&custom_validation_error_ty);
-        let fields = fields
-            .enumerate()
-            .map(|(_, f)| ValidatedFieldDeriv::new(&f, custom_validation_error_ty.clone()))
+        let mut custom_validation_error_ty: syn::Type =
+            Self::validation_error_from_attrs(&ast.attrs);
+        let error_enum = match &custom_validation_error_ty {
+            syn::Type::Path(p) if p.qself.is_none() && p.path.is_ident("auto") => {
+                let error_enum = format_ident!("{}ValidationError", ast.ident);
+                custom_validation_error_ty = parse_quote!(#error_enum);
+                Some(error_enum)
+            }
+            _ => None,
+        };
+        let options = ValidationOptions::from_attrs(&ast.attrs)?;
+        let mut fields: Vec<ValidatedFieldDeriv> = fields
+            .map(|f| {
+                ValidatedFieldDeriv::new(
+                    f,
+                    custom_validation_error_ty.clone(),
+                    options.context.clone(),
+                )
+            })
             .collect::<Result<_, _>>()?;
+        for field in &mut fields {
+            match &error_enum {
+                Some(error_enum) => field.use_error_enum(error_enum),
+                None => {
+                    if let Some(ty) = field.declared_error_ty() {
+                        return Err(Error::new(
+                            ty.span(),
+                            "`error_type` requires `#[validation_error(auto)]` on the struct",
+                        ));
+                    }
+                }
+            }
+        }
+        if error_enum.is_some() {
+            check_unique_names(&fields, "error variant", |f| f.error_variant())?;
+        }
+        if options.sealed {
+            for field in &fields {
+                field.check_private()?;
+            }
+        }
+        if options.setters || options.patch {
+            if let Some(when) = fields.iter().find_map(|f| f.when_condition()) {
+                return Err(Error::new_spanned(
+                    when,
+                    "`when` is not supported with `setters` or `patch`, which only validate the \
+                     new value and cannot evaluate the condition against the whole input",
+                ));
+            }
+        }
+        if options.builder == Some(BuilderKind::Typestate) {
+            check_unique_names(&fields, "typestate builder type parameter", |f| {
+                f.typestate_param()
+            })?;
+        }
         Ok(ValidatedDeriv {
             visibility: &ast.vis,
             name: &ast.ident,
@@ -36,6 +88,8 @@ impl<'a> ValidatedDeriv<'a> {
             generics: &ast.generics,
             fields,
             custom_validation_error_ty,
+            error_enum,
+            options,
         })
     }
 
@@ -47,7 +101,7 @@ impl<'a> ValidatedDeriv<'a> {
                 let err: syn::Type = a.parse_args().expect("parse validation_error");
                 err
             })
-            .last()
+            .next_back()
             .unwrap_or(parse_quote! {
                 ::std::string::String
             })
@@ -67,12 +121,79 @@ impl<'a> ValidatedDeriv<'a> {
             }));
         let unvalidated_struct = self.build_unvalidated_struct()?;
         let validate_impl = self.build_validate_impl()?;
+        let setters = if self.options.setters {
+            self.build_setters()
+        } else {
+            quote!()
+        };
+        let getters = if self.options.sealed {
+            self.build_getters()
+        } else {
+            quote!()
+        };
+        let patch = if self.options.patch {
+            self.build_patch()
+        } else {
+            quote!()
+        };
+        let partial = if self.options.partial {
+            self.build_partial()
+        } else {
+            quote!()
+        };
+        let from_validated_impl = self.build_from_validated_impl();
+        let error_enum = self.build_error_enum();
         Ok(quote! {
+            #error_enum
             #unvalidated_struct
             #validate_impl
+            #from_validated_impl
+            #setters
+            #getters
+            #patch
+            #partial
         })
     }
 
+    /// Builds the error enum for `#[validation_error(auto)]`, with a variant holding the error
+    /// of each field that has validators
+    ///
+    /// With `dedup_errors` it also derives `PartialEq`, `Eq` and `Hash`, which deduplication
+    /// requires, so the error types of the fields have to implement them as well.
+    fn build_error_enum(&self) -> TokenStream {
+        let error_enum = match &self.error_enum {
+            Some(error_enum) => error_enum,
+            None => return quote!(),
+        };
+        let vis = &self.visibility;
+        let (variants, tys): (Vec<_>, Vec<_>) = self
+            .fields
+            .iter()
+            .filter_map(|f| Some((f.error_variant()?, f.get_error_ty())))
+            .unzip();
+        let dedup_derives = self
+            .options
+            .dedup_errors
+            .then(|| quote!(, ::std::cmp::PartialEq, ::std::cmp::Eq, ::std::hash::Hash));
+        quote! {
+            #[automatically_derived]
+            #[derive(Debug #dedup_derives)]
+            #vis enum #error_enum {
+                #( #variants(#tys), )*
+            }
+            #[automatically_derived]
+            impl ::std::fmt::Display for #error_enum {
+                fn fmt(&self, f: &mut ::std::fmt::Formatter<'_>) -> ::std::fmt::Result {
+                    match *self {
+                        #( #error_enum::#variants(ref e) => ::std::fmt::Display::fmt(e, f), )*
+                    }
+                }
+            }
+            #[automatically_derived]
+            impl ::std::error::Error for #error_enum {}
+        }
+    }
+
     fn build_unvalidated_struct(&self) -> Result<TokenStream, Error> {
         let vis = &self.visibility;
         let name = &self.unvalidated_name;
@@ -81,50 +202,381 @@ impl<'a> ValidatedDeriv<'a> {
             .fields
             .iter()
             .map(|f| f.build_unvalidated_struct_repr());
+        let builder = match self.options.builder {
+            Some(BuilderKind::Plain) => self.build_builder(),
+            Some(BuilderKind::Typestate) => self.build_typestate_builder(),
+            None => quote!(),
+        };
+        let check = self.build_check();
         Ok(quote! {
             #[automatically_derived]
             #vis struct #name #ty_generics  {
                 #( #fields, )*
             }
+            #builder
+            #check
         })
     }
 
-    fn build_validate_impl(&self) -> Result<TokenStream, Error> {
-        let name = &self.name;
+    /// Builds `UnvalidatedA::check(&self)`, running the validators taking the value by reference
+    /// without moving the unvalidated struct
+    ///
+    /// This is only generated when some validator takes the value by reference. It takes the
+    /// context when the struct declares one.
+    fn build_check(&self) -> TokenStream {
+        if !self.fields.iter().any(|f| f.is_checkable()) {
+            return quote!();
+        }
+        let vis = &self.visibility;
         let unvalidated_name = &self.unvalidated_name;
         let ety = &self.custom_validation_error_ty;
+        let new_errors = self.build_new_errors();
         let (impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
-        // let validator_fields = &self.validator_fields();
-        let has_any_validated_fields = self.fields.iter().any(|f| f.is_validated());
-        // dbg!(&self.fields);
-        let body = if has_any_validated_fields {
-            let match_validator_calls = &self.match_validator_calls();
-            let match_validator_ok = &self.match_validator_ok();
-            let match_validator_nok = &self.match_validator_nok();
-            let match_validator_error_push = &self.match_validator_error_push();
-            let constructor = self.constructor();
-            let validator_assertions = self.fields.iter().map(|f| f.build_field_assertions());
-            quote! {
-                #( #validator_assertions )*
-                match (#match_validator_calls) {
-                    (#match_validator_ok) => ::std::result::Result::Ok(#constructor),
-                    (#match_validator_nok) => {
-                        let mut errors: ::std::vec::Vec<#ety> = ::std::vec::Vec::new();
-                        #match_validator_error_push
+        let params = self.build_entry_params();
+        let checks = self.fields.iter().filter_map(|f| {
+            let check = f.build_check()?;
+            let capture = f.build_value_capture(quote!(self));
+            let push = f.build_error_push();
+            Some(quote! {
+                if let ::std::result::Result::Err(e) = #check {
+                    #capture
+                    #push
+                }
+            })
+        });
+        let ignore_ctx = self.options.context.iter().map(|_| quote!(let _ = ctx;));
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #unvalidated_name #ty_generics {
+                #vis fn check(
+                    &self,
+                    #params
+                ) -> ::core::result::Result<(), ::valibuk::error::ValidationErrors<#ety>> {
+                    #( #ignore_ctx )*
+                    let mut errors: ::valibuk::error::ValidationErrors<#ety> =
+                        #new_errors;
+                    #( #checks )*
+                    if errors.is_empty() {
+                        ::std::result::Result::Ok(())
+                    } else {
                         ::std::result::Result::Err(errors)
                     }
                 }
             }
+        }
+    }
+
+    /// Builds `UnvalidatedA::builder()` with a setter for each field
+    ///
+    /// The builder ends either in `build()`, yielding the unvalidated struct, or in `validate()`,
+    /// running the same validation as the validation entry point.
+    fn build_builder(&self) -> TokenStream {
+        let vis = &self.visibility;
+        let name = self.name;
+        let unvalidated_name = &self.unvalidated_name;
+        let builder_name = format_ident!("{}Builder", unvalidated_name);
+        let ety = &self.custom_validation_error_ty;
+        let (impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        let fields = self.fields.iter().map(|f| f.build_builder_field());
+        let names: Vec<_> = self.fields.iter().map(|f| f.get_name()).collect();
+        let setters = self.fields.iter().map(|f| f.build_builder_setter(vis));
+        let build_fields = self.fields.iter().map(|f| f.build_builder_build());
+        let asyncness = self.asyncness();
+        let params = self.build_entry_params();
+        let validate = self.build_entry_call(quote!(self.build()?));
+        quote! {
+            #[automatically_derived]
+            #vis struct #builder_name #ty_generics {
+                #( #fields, )*
+            }
+            #[automatically_derived]
+            impl #impl_generics #unvalidated_name #ty_generics {
+                #vis fn builder() -> #builder_name #ty_generics {
+                    #builder_name {
+                        #( #names: ::std::option::Option::None, )*
+                    }
+                }
+            }
+            #[automatically_derived]
+            impl #impl_generics #builder_name #ty_generics {
+                #( #setters )*
+
+                #vis fn build(
+                    self
+                ) -> ::core::result::Result<#unvalidated_name #ty_generics, ::valibuk::builder::MissingField> {
+                    ::core::result::Result::Ok(#unvalidated_name {
+                        #( #build_fields, )*
+                    })
+                }
+
+                #vis #asyncness fn validate(
+                    self,
+                    #params
+                ) -> ::core::result::Result<
+                    #name #ty_generics,
+                    ::valibuk::builder::BuilderError<::valibuk::error::ValidationErrors<#ety>>
+                > {
+                    #validate.map_err(::valibuk::builder::BuilderError::Invalid)
+                }
+            }
+        }
+    }
+
+    /// Builds `UnvalidatedA::builder()` tracking in its type which required fields are set
+    ///
+    /// Each field without a default gets a type parameter on the builder, which is either
+    /// `valibuk::builder::Unset` or `valibuk::builder::Set<T>`. `build()` and `validate()` are
+    /// only implemented once all of them are `Set`, so forgetting a field fails to compile.
+    fn build_typestate_builder(&self) -> TokenStream {
+        let vis = &self.visibility;
+        let name = self.name;
+        let unvalidated_name = &self.unvalidated_name;
+        let builder_name = format_ident!("{}Builder", unvalidated_name);
+        let ety = &self.custom_validation_error_ty;
+        let (_impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        let params = generic_params(self.generics);
+        let args = generic_args(self.generics);
+        let states: Vec<_> = self
+            .fields
+            .iter()
+            .filter_map(|f| f.typestate_param())
+            .collect();
+        let fields = self
+            .fields
+            .iter()
+            .map(|f| f.build_typestate_builder_field());
+        let initial_fields = self
+            .fields
+            .iter()
+            .map(|f| f.build_typestate_builder_initial());
+        let unset = states.iter().map(|_| quote!(::valibuk::builder::Unset));
+        let set = self
+            .fields
+            .iter()
+            .filter(|f| f.typestate_param().is_some())
+            .map(|f| {
+                let ty = f.get_unvalidated_ty();
+                quote!(::valibuk::builder::Set<#ty>)
+            });
+        let setters = self.fields.iter().map(|f| {
+            let name = f.get_name();
+            let ty = f.get_unvalidated_ty();
+            let moved = self.fields.iter().map(|o| {
+                let other = o.get_name();
+                if other.to_string() == name.to_string() {
+                    f.build_typestate_builder_set()
+                } else {
+                    quote!(#other: self.#other)
+                }
+            });
+            let return_states = self.fields.iter().filter_map(|o| {
+                let state = o.typestate_param()?;
+                if o.get_name().to_string() == name.to_string() {
+                    Some(quote!(::valibuk::builder::Set<#ty>))
+                } else {
+                    Some(quote!(#state))
+                }
+            });
+            quote! {
+                #vis fn #name(self, #name: impl ::std::convert::Into<#ty>) -> #builder_name<#( #args, )* #( #return_states, )*> {
+                    #builder_name {
+                        #( #moved, )*
+                        __marker: ::core::marker::PhantomData,
+                    }
+                }
+            }
+        });
+        let build_fields = self
+            .fields
+            .iter()
+            .map(|f| f.build_typestate_builder_build());
+        let asyncness = self.asyncness();
+        let entry_params = self.build_entry_params();
+        let validate = self.build_entry_call(quote!(self.build()));
+        quote! {
+            #[automatically_derived]
+            #vis struct #builder_name<#( #params, )* #( #states, )*> {
+                #( #fields, )*
+                __marker: ::core::marker::PhantomData<fn() -> #unvalidated_name #ty_generics>,
+            }
+            #[automatically_derived]
+            impl<#( #params, )*> #unvalidated_name #ty_generics {
+                #vis fn builder() -> #builder_name<#( #args, )* #( #unset, )*> {
+                    #builder_name {
+                        #( #initial_fields, )*
+                        __marker: ::core::marker::PhantomData,
+                    }
+                }
+            }
+            #[automatically_derived]
+            impl<#( #params, )* #( #states, )*> #builder_name<#( #args, )* #( #states, )*> {
+                #( #setters )*
+            }
+            #[automatically_derived]
+            impl<#( #params, )*> #builder_name<#( #args, )* #( #set, )*> {
+                #vis fn build(self) -> #unvalidated_name #ty_generics {
+                    #unvalidated_name {
+                        #( #build_fields, )*
+                    }
+                }
+
+                #vis #asyncness fn validate(
+                    self,
+                    #entry_params
+                ) -> ::core::result::Result<#name #ty_generics, ::valibuk::error::ValidationErrors<#ety>> {
+                    #validate
+                }
+            }
+        }
+    }
+
+    /// Builds `From<A> for UnvalidatedA`, turning a valid instance back into an unvalidated one
+    ///
+    /// This is left out when a validator changes the type of its field and there is no `into`
+    /// mapping back.
+    fn build_from_validated_impl(&self) -> TokenStream {
+        if !self.fields.iter().all(|f| f.is_reversible()) {
+            return quote!();
+        }
+        let name = self.name;
+        let unvalidated_name = &self.unvalidated_name;
+        let (impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        let fields = self
+            .fields
+            .iter()
+            .map(|f| f.build_unvalidated_from_validated());
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics ::std::convert::From<#name #ty_generics> for #unvalidated_name #ty_generics {
+                fn from(validated: #name #ty_generics) -> Self {
+                    #unvalidated_name {
+                        #( #fields, )*
+                    }
+                }
+            }
+        }
+    }
+
+    /// Builds `try_set_<field>` setters on the validated struct, which keep it valid
+    fn build_setters(&self) -> TokenStream {
+        let vis = &self.visibility;
+        let name = self.name;
+        let (impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        let setters = self.fields.iter().map(|f| f.build_try_setter(vis));
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #name #ty_generics {
+                #( #setters )*
+            }
+        }
+    }
+
+    /// Builds read-only getters for the fields of a sealed struct
+    fn build_getters(&self) -> TokenStream {
+        let vis = &self.visibility;
+        let name = self.name;
+        let (impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        let getters = self.fields.iter().map(|f| f.build_getter(vis));
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #name #ty_generics {
+                #( #getters )*
+            }
+        }
+    }
+
+    /// `async` when the validation entry point is async
+    fn asyncness(&self) -> Option<Token![async]> {
+        if self.fields.iter().any(|f| f.is_async()) {
+            Some(Token![async](proc_macro2::Span::call_site()))
         } else {
-            let constructor = self.unvalidated_constructor();
+            None
+        }
+    }
+
+    /// Parameters the validation entry point takes besides the unvalidated struct
+    fn build_entry_params(&self) -> TokenStream {
+        let ctx = self.options.context.iter();
+        quote! {
+            #( ctx: &#ctx, )*
+        }
+    }
+
+    /// Emits the call to the validation entry point for `unvalidated`
+    ///
+    /// This is `TryFrom`, `validate_with` or `validate_async` (awaited) depending on the validators
+    /// present. The context, when required, is expected to be bound to `ctx`.
+    fn build_entry_call(&self, unvalidated: TokenStream) -> TokenStream {
+        let name = self.name;
+        let unvalidated_name = &self.unvalidated_name;
+        let (_impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        let ctx = self.options.context.iter().map(|_| quote!(ctx));
+        if self.asyncness().is_some() {
             quote! {
-                Ok(#constructor)
+                #name::validate_async(#unvalidated, #( #ctx, )*).await
             }
-        };
+        } else if self.options.context.is_some() {
+            quote! {
+                #name::validate_with(#unvalidated, ctx)
+            }
+        } else {
+            quote! {
+                <#name #ty_generics as ::std::convert::TryFrom<#unvalidated_name #ty_generics>>::try_from(#unvalidated)
+            }
+        }
+    }
+
+    fn build_validate_impl(&self) -> Result<TokenStream, Error> {
+        let validate_impl =
+            self.build_default_validate_impl(self.build_validate_body(Entry::Plain));
+        let validate_for = self.build_validate_for_impl();
+        let validate_with_warnings = self.build_validate_with_warnings_impl();
         Ok(quote! {
+            #validate_impl
+            #validate_for
+            #validate_with_warnings
+        })
+    }
+
+    /// Builds the validation entry point running the validators without groups
+    fn build_default_validate_impl(&self, body: TokenStream) -> TokenStream {
+        let name = &self.name;
+        let unvalidated_name = &self.unvalidated_name;
+        let ety = &self.custom_validation_error_ty;
+        let (impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        if self.asyncness().is_some() {
+            let vis = &self.visibility;
+            let ctx = self.options.context.iter();
+            return quote! {
+                #[automatically_derived]
+                impl #impl_generics #name #ty_generics {
+                    #vis async fn validate_async(
+                        unvalidated: #unvalidated_name #ty_generics,
+                        #( ctx: &#ctx, )*
+                    ) -> ::core::result::Result<Self, ::valibuk::error::ValidationErrors<#ety>> {
+                        #body
+                    }
+                }
+            };
+        }
+        if let Some(ctx) = &self.options.context {
+            let vis = &self.visibility;
+            return quote! {
+                #[automatically_derived]
+                impl #impl_generics #name #ty_generics {
+                    #vis fn validate_with(
+                        unvalidated: #unvalidated_name #ty_generics,
+                        ctx: &#ctx,
+                    ) -> ::core::result::Result<Self, ::valibuk::error::ValidationErrors<#ety>> {
+                        #body
+                    }
+                }
+            };
+        }
+        quote! {
             #[automatically_derived]
             impl #impl_generics ::std::convert::TryFrom<#unvalidated_name #ty_generics>  for #name #ty_generics {
-                type Error = ::std::vec::Vec<#ety>;
+                type Error = ::valibuk::error::ValidationErrors<#ety>;
 
                 fn try_from(
                     unvalidated: #unvalidated_name #ty_generics
@@ -132,7 +584,352 @@ impl<'a> ValidatedDeriv<'a> {
                     #body
                 }
             }
-        })
+        }
+    }
+
+    /// Builds `A::validate_for::<G>(unvalidated)`, also running the validators of group `G`
+    ///
+    /// This is only generated when some validator has groups.
+    fn build_validate_for_impl(&self) -> TokenStream {
+        if !self.fields.iter().any(|f| f.has_groups()) {
+            return quote!();
+        }
+        let vis = &self.visibility;
+        let name = &self.name;
+        let unvalidated_name = &self.unvalidated_name;
+        let ety = &self.custom_validation_error_ty;
+        let (impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        let asyncness = self.asyncness();
+        let params = self.build_entry_params();
+        let body = self.build_validate_body(Entry::Grouped);
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #name #ty_generics {
+                #vis #asyncness fn validate_for<__Group: ::valibuk::groups::Group>(
+                    unvalidated: #unvalidated_name #ty_generics,
+                    #params
+                ) -> ::core::result::Result<Self, ::valibuk::error::ValidationErrors<#ety>> {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// Builds `A::validate_with_warnings(unvalidated)`, yielding the validated struct together
+    /// with the failures of the `severity = warn` validators
+    ///
+    /// This is only generated when some validator has `severity = warn`.
+    fn build_validate_with_warnings_impl(&self) -> TokenStream {
+        if !self.fields.iter().any(|f| f.has_warnings()) {
+            return quote!();
+        }
+        let vis = &self.visibility;
+        let name = &self.name;
+        let unvalidated_name = &self.unvalidated_name;
+        let ety = &self.custom_validation_error_ty;
+        let (impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        let asyncness = self.asyncness();
+        let params = self.build_entry_params();
+        let body = self.build_validate_body(Entry::Warnings);
+        quote! {
+            #[automatically_derived]
+            impl #impl_generics #name #ty_generics {
+                #vis #asyncness fn validate_with_warnings(
+                    unvalidated: #unvalidated_name #ty_generics,
+                    #params
+                ) -> ::core::result::Result<
+                    ::valibuk::Validated<Self, ::valibuk::error::ValidationErrors<#ety>>,
+                    ::valibuk::error::ValidationErrors<#ety>
+                > {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// Builds `APartial`, holding each field that validated successfully, and
+    /// `A::validate_partial(unvalidated)`
+    fn build_partial(&self) -> TokenStream {
+        let vis = &self.visibility;
+        let name = self.name;
+        let partial_name = format_ident!("{}Partial", name);
+        let unvalidated_name = &self.unvalidated_name;
+        let ety = &self.custom_validation_error_ty;
+        let (impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        let fields = self.fields.iter().map(|f| f.build_partial_struct_repr());
+        let asyncness = self.asyncness();
+        let params = self.build_entry_params();
+        let body = self.build_validate_body(Entry::Partial);
+        quote! {
+            #[automatically_derived]
+            #vis struct #partial_name #ty_generics {
+                #( #fields, )*
+            }
+            #[automatically_derived]
+            impl #impl_generics ::valibuk::partial::Partial for #name #ty_generics {
+                type Fields = #partial_name #ty_generics;
+                type Error = #ety;
+            }
+            #[automatically_derived]
+            impl #impl_generics #name #ty_generics {
+                #vis #asyncness fn validate_partial(
+                    unvalidated: #unvalidated_name #ty_generics,
+                    #params
+                ) -> ::valibuk::partial::PartialResult<Self> {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// Builds the expression running all the validators against `unvalidated`
+    ///
+    /// Validators requiring a context expect it to be bound to `ctx`. When there are async
+    /// validators, the expression has to be placed in an async context. The `entry` decides
+    /// which validators run and what is yielded on success.
+    fn build_validate_body(&self, entry: Entry) -> TokenStream {
+        let has_any_validated_fields = self.fields.iter().any(|f| f.is_validated());
+        // the warnings are run once the fields are bound to their names, which may include `ctx`
+        let warnings_ctx = self
+            .options
+            .context
+            .iter()
+            .filter(|_| entry == Entry::Warnings)
+            .map(|_| quote!(let __ctx = ctx;));
+        if has_any_validated_fields {
+            let validator_assertions = self.fields.iter().map(|f| f.build_field_assertions());
+            let conditions = self.fields.iter().map(|f| f.build_condition());
+            let captures = self
+                .fields
+                .iter()
+                .map(|f| f.build_value_capture(quote!(unvalidated)));
+            let async_join = self.build_async_join();
+            let calls = self.match_validator_calls(entry == Entry::Grouped);
+            let validator_match = if entry == Entry::Partial {
+                self.build_partial_match(calls)
+            } else {
+                self.build_validator_match(calls, self.build_ok(entry))
+            };
+            quote! {
+                #( #validator_assertions )*
+                #( #warnings_ctx )*
+                #( #conditions )*
+                #( #captures )*
+                #async_join
+                #validator_match
+            }
+        } else if entry == Entry::Warnings {
+            let validator_assertions = self.fields.iter().map(|f| f.build_field_assertions());
+            let names = self.fields.iter().map(|f| f.get_name());
+            let inputs = self.fields.iter().map(|f| f.build_input());
+            let ok = self.build_ok(entry);
+            quote! {
+                #( #validator_assertions )*
+                #( #warnings_ctx )*
+                #( let #names = #inputs; )*
+                #ok
+            }
+        } else if entry == Entry::Partial {
+            let constructor = self.unvalidated_constructor();
+            quote! {
+                ::valibuk::partial::PartialResult::Valid(#constructor)
+            }
+        } else {
+            let constructor = self.unvalidated_constructor();
+            quote! {
+                Ok(#constructor)
+            }
+        }
+    }
+
+    /// Creates the empty errors, limited and deduplicated as configured
+    fn build_new_errors(&self) -> TokenStream {
+        let limit = self
+            .options
+            .max_errors
+            .iter()
+            .map(|max| quote!(.with_limit(#max)));
+        let dedup = self.options.dedup_errors.then(|| quote!(.deduplicated()));
+        quote! {
+            ::valibuk::error::ValidationErrors::new() #( #limit )* #dedup
+        }
+    }
+
+    /// Yields the validated struct, built from the validated fields bound to their names
+    fn build_ok(&self, entry: Entry) -> TokenStream {
+        let ety = &self.custom_validation_error_ty;
+        let new_errors = self.build_new_errors();
+        let constructor = self.constructor();
+        if entry == Entry::Warnings {
+            let warnings = self.fields.iter().map(|f| f.build_warnings_push());
+            quote! {{
+                let mut __warnings: ::valibuk::error::ValidationErrors<#ety> =
+                        #new_errors;
+                #( #warnings )*
+                ::std::result::Result::Ok(::valibuk::Validated {
+                    value: #constructor,
+                    warnings: __warnings,
+                })
+            }}
+        } else {
+            quote!(::std::result::Result::Ok(#constructor))
+        }
+    }
+
+    /// Matches on the results of the validator `calls` of all fields
+    ///
+    /// Yields `ok` when all of them succeeded, or all the errors otherwise.
+    fn build_validator_match(&self, calls: TokenStream, ok: TokenStream) -> TokenStream {
+        let ety = &self.custom_validation_error_ty;
+        let new_errors = self.build_new_errors();
+        let match_validator_ok = &self.match_validator_ok();
+        let match_validator_nok = &self.match_validator_nok();
+        let match_validator_error_push = &self.match_validator_error_push();
+        quote! {
+            match (#calls) {
+                (#match_validator_ok) => #ok,
+                (#match_validator_nok) => {
+                    let mut errors: ::valibuk::error::ValidationErrors<#ety> =
+                        #new_errors;
+                    #match_validator_error_push
+                    ::std::result::Result::Err(errors)
+                }
+            }
+        }
+    }
+
+    /// Matches on the results of the validator `calls` of all fields
+    ///
+    /// Yields the validated struct when all of them succeeded, or the partial struct with the
+    /// fields that succeeded together with the errors otherwise.
+    fn build_partial_match(&self, calls: TokenStream) -> TokenStream {
+        let ety = &self.custom_validation_error_ty;
+        let new_errors = self.build_new_errors();
+        let partial_name = format_ident!("{}Partial", self.name);
+        let match_validator_ok = &self.match_validator_ok();
+        let names = self.fields.iter().map(|f| f.get_name());
+        let partial_fields = self.fields.iter().map(|f| f.build_partial_field());
+        let constructor = self.constructor();
+        quote! {
+            match (#calls) {
+                (#match_validator_ok) => ::valibuk::partial::PartialResult::Valid(#constructor),
+                (#( #names, )*) => {
+                    let mut errors: ::valibuk::error::ValidationErrors<#ety> =
+                        #new_errors;
+                    let fields = #partial_name {
+                        #( #partial_fields, )*
+                    };
+                    ::valibuk::partial::PartialResult::Invalid { fields, errors }
+                }
+            }
+        }
+    }
+
+    /// Builds `PatchA`, with every field optional, and `a.apply_patch(patch)`
+    ///
+    /// Only the fields present in the patch are validated, the others are taken from the
+    /// validated struct as they are.
+    fn build_patch(&self) -> TokenStream {
+        let vis = &self.visibility;
+        let name = self.name;
+        let patch_name = format_ident!("Patch{}", name);
+        let ety = &self.custom_validation_error_ty;
+        let (impl_generics, ty_generics, _where_clause) = self.generics.split_for_impl();
+        let fields = self.fields.iter().map(|f| f.build_patch_struct_repr());
+        let names: Vec<_> = self.fields.iter().map(|f| f.get_name()).collect();
+        let calls: Vec<_> = self
+            .fields
+            .iter()
+            .map(|f| f.build_patch_validator_call())
+            .collect();
+        let params = self.build_entry_params();
+        let asyncness = self.asyncness();
+        let body = if self.fields.iter().any(|f| f.is_validated()) {
+            let validator_assertions = self.fields.iter().map(|f| f.build_field_assertions());
+            let captures = self.fields.iter().map(|f| f.build_patch_value_capture());
+            let validator_match =
+                self.build_validator_match(quote!(#( #calls, )*), self.build_ok(Entry::Plain));
+            quote! {
+                #( #validator_assertions )*
+                #( #captures )*
+                #validator_match
+            }
+        } else {
+            quote! {
+                ::std::result::Result::Ok(#name {
+                    #( #names: #calls, )*
+                })
+            }
+        };
+        quote! {
+            #[automatically_derived]
+            #vis struct #patch_name #ty_generics {
+                #( #fields, )*
+            }
+            #[automatically_derived]
+            impl #impl_generics ::std::default::Default for #patch_name #ty_generics {
+                fn default() -> Self {
+                    #patch_name {
+                        #( #names: ::std::option::Option::None, )*
+                    }
+                }
+            }
+            #[automatically_derived]
+            impl #impl_generics #name #ty_generics {
+                #vis #asyncness fn apply_patch(
+                    self,
+                    patch: #patch_name #ty_generics,
+                    #params
+                ) -> ::core::result::Result<Self, ::valibuk::error::ValidationErrors<#ety>> {
+                    #body
+                }
+            }
+        }
+    }
+
+    /// Starts the async validators of all the fields and awaits them concurrently
+    ///
+    /// Each result is bound to the [async binding][ValidatedFieldDeriv::async_binding] of its
+    /// field, to be picked up by the validator match.
+    fn build_async_join(&self) -> TokenStream {
+        let fields: Vec<_> = self.fields.iter().filter(|f| f.is_async()).collect();
+        if fields.is_empty() {
+            return quote!();
+        }
+        let bindings: Vec<_> = fields.iter().map(|f| f.async_binding()).collect();
+        let futures: Vec<_> = bindings
+            .iter()
+            .map(|b| format_ident!("{}_future", b))
+            .collect();
+        let calls = fields.iter().map(|f| f.build_async_validator_call());
+        let tys = fields.iter().map(|f| f.get_ty());
+        quote! {
+            #(
+                let mut #futures = ::core::pin::pin!(#calls);
+                let mut #bindings: ::core::option::Option<::std::result::Result<#tys, _>> =
+                    ::core::option::Option::None;
+            )*
+            ::core::future::poll_fn(|cx| {
+                let mut ready = true;
+                #(
+                    if #bindings.is_none() {
+                        match ::core::future::Future::poll(#futures.as_mut(), cx) {
+                            ::core::task::Poll::Ready(r) => #bindings = ::core::option::Option::Some(r),
+                            ::core::task::Poll::Pending => ready = false,
+                        }
+                    }
+                )*
+                if ready {
+                    ::core::task::Poll::Ready(())
+                } else {
+                    ::core::task::Poll::Pending
+                }
+            })
+            .await;
+            #(
+                let #bindings = #bindings.expect("async validator completed");
+            )*
+        }
     }
 
     fn constructor(&self) -> TokenStream {
@@ -158,8 +955,14 @@ impl<'a> ValidatedDeriv<'a> {
         }
     }
 
-    fn match_validator_calls(&self) -> TokenStream {
-        let fields = self.fields.iter().map(|f| f.build_match_validator_call());
+    fn match_validator_calls(&self, grouped: bool) -> TokenStream {
+        let fields = self.fields.iter().map(|f| {
+            if grouped {
+                f.build_group_validator_call()
+            } else {
+                f.build_match_validator_call()
+            }
+        });
         quote! {
             #( #fields, )*
         }
@@ -173,7 +976,7 @@ impl<'a> ValidatedDeriv<'a> {
     }
 
     fn match_validator_nok(&self) -> TokenStream {
-        let fields = self.fields.iter().map(|f| f.get_name());
+        let fields = self.fields.iter().map(|f| f.build_match_validator_nok());
         quote! {
             #( #fields, )*
         }
@@ -187,5 +990,169 @@ impl<'a> ValidatedDeriv<'a> {
     }
 }
 
+/// Validation entry point a validation body is built for
+#[derive(Debug, Clone, Copy, PartialEq)]
+enum Entry {
+    /// `TryFrom`, `validate_with` or `validate_async`
+    Plain,
+    /// `validate_for`, also running the validators of a group
+    Grouped,
+    /// `validate_with_warnings`, also running the `severity = warn` validators
+    Warnings,
+    /// `validate_partial`, yielding the fields that succeeded when others failed
+    Partial,
+}
+
+/// Fails when two fields get the same generated `name`, e.g. `a` and `_a` both get `A`
+///
+/// The error points at the second field, `what` describes the generated item.
+fn check_unique_names(
+    fields: &[ValidatedFieldDeriv],
+    what: &str,
+    name: impl Fn(&ValidatedFieldDeriv) -> Option<syn::Ident>,
+) -> Result<(), Error> {
+    let mut seen: Vec<(syn::Ident, &syn::Ident)> = Vec::new();
+    for (field, generated) in fields.iter().filter_map(|f| Some((f, name(f)?))) {
+        if let Some((_, other)) = seen.iter().find(|(g, _)| *g == generated) {
+            return Err(Error::new(
+                field.get_ident().span(),
+                format!(
+                    "`{}` and `{}` both map to the {} `{}`, rename one of them",
+                    other,
+                    field.get_ident(),
+                    what,
+                    generated
+                ),
+            ));
+        }
+        seen.push((generated, field.get_ident()));
+    }
+    Ok(())
+}
+
+/// Generic parameters usable in an impl, i.e. without defaults, e.g. `'a, T: X` for `<'a, T: X = Y>`
+fn generic_params(generics: &syn::Generics) -> Vec<syn::GenericParam> {
+    generics
+        .params
+        .iter()
+        .cloned()
+        .map(|mut p| {
+            match &mut p {
+                syn::GenericParam::Type(t) => {
+                    t.eq_token = None;
+                    t.default = None;
+                }
+                syn::GenericParam::Const(c) => {
+                    c.eq_token = None;
+                    c.default = None;
+                }
+                syn::GenericParam::Lifetime(_) => {}
+            }
+            p
+        })
+        .collect()
+}
+
+/// Generic arguments of a type with the given generic parameters, e.g. `'a, T` for `<'a, T: X>`
+fn generic_args(generics: &syn::Generics) -> Vec<TokenStream> {
+    generics
+        .params
+        .iter()
+        .map(|p| match p {
+            syn::GenericParam::Type(t) => {
+                let ident = &t.ident;
+                quote!(#ident)
+            }
+            syn::GenericParam::Lifetime(l) => {
+                let lifetime = &l.lifetime;
+                quote!(#lifetime)
+            }
+            syn::GenericParam::Const(c) => {
+                let ident = &c.ident;
+                quote!(#ident)
+            }
+        })
+        .collect()
+}
+
+/// Parsed contents of the `#[validation(...)]` struct attribute
+#[derive(Debug, Default)]
+struct ValidationOptions {
+    /// `context = T`: validators marked with `context` receive a `&T`, the entry point becomes
+    /// `validate_with(unvalidated, &ctx)` instead of `TryFrom`
+    context: Option<syn::Type>,
+    /// `builder` or `builder = typestate`: generate `UnvalidatedA::builder()`
+    builder: Option<BuilderKind>,
+    /// `setters`: generate `try_set_<field>` setters on the validated struct
+    setters: bool,
+    /// `patch`: generate `PatchA` and `a.apply_patch(patch)`
+    patch: bool,
+    /// `partial`: generate `APartial` and `A::validate_partial(unvalidated)`
+    partial: bool,
+    /// `sealed`: require private fields and generate getters, so that outside of its module the
+    /// struct can only be obtained through validation
+    sealed: bool,
+    /// `max_errors = N`: record at most N failures, the others are only counted
+    max_errors: Option<syn::LitInt>,
+    /// `dedup_errors`: record identical errors of a field once
+    dedup_errors: bool,
+}
+
+#[derive(Debug, PartialEq)]
+enum BuilderKind {
+    /// Checks that the required fields are set at runtime
+    Plain,
+    /// Checks that the required fields are set at compile time
+    Typestate,
+}
+
+impl ValidationOptions {
+    fn from_attrs(attrs: &[Attribute]) -> Result<ValidationOptions, Error> {
+        let mut options = ValidationOptions::default();
+        for attr in attrs.iter().filter(|a| a.path.is_ident("validation")) {
+            attr.parse_args_with(|input: ParseStream| options.parse_into(input))?;
+        }
+        Ok(options)
+    }
+
+    fn parse_into(&mut self, input: ParseStream) -> syn::Result<()> {
+        while !input.is_empty() {
+            let key: syn::Ident = input.parse()?;
+            match key.to_string().as_str() {
+                "context" => {
+                    input.parse::<Token![=]>()?;
+                    self.context = Some(input.parse()?);
+                }
+                "setters" => self.setters = true,
+                "sealed" => self.sealed = true,
+                "patch" => self.patch = true,
+                "partial" => self.partial = true,
+                "dedup_errors" => self.dedup_errors = true,
+                "max_errors" => {
+                    input.parse::<Token![=]>()?;
+                    let max: syn::LitInt = input.parse()?;
+                    max.base10_parse::<usize>()?;
+                    self.max_errors = Some(max);
+                }
+                "builder" => {
+                    self.builder = Some(BuilderKind::Plain);
+                    if input.parse::<Option<Token![=]>>()?.is_some() {
+                        let kind: syn::Ident = input.parse()?;
+                        if kind != "typestate" {
+                            return Err(Error::new(kind.span(), "expected `typestate`"));
+                        }
+                        self.builder = Some(BuilderKind::Typestate);
+                    }
+                }
+                _ => return Err(Error::new(key.span(), "unknown validation option")),
+            }
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+        Ok(())
+    }
+}
+
 #[cfg(test)]
 mod test {}
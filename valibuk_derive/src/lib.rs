@@ -7,11 +7,49 @@ use valibuk_core::valibuk_core;
 /// Only structs with named fields are supported at this time
 ///
 /// The available attributes:
-/// `validator` is set on a field and specifies the function to be run for validation, the function
-/// should return `Result<T, E>`, where T is the type of the field under validation and E is the
-/// error type set by `validation_error` attribute, or `String` by default.
+/// * `validator` is set on a field and specifies the function to be run for validation, the
+///   function should return `Result<T, E>`, where T is the type of the field under validation and
+///   E is the error type set by `validation_error` attribute, or `String` by default, or any error
+///   type it implements `From` for. The built-in validators of `valibuk::validators`, e.g.
+///   `length(min, max)`, are imported or referred to by path. Options after the function:
+///   * `nested` fails with the `ValidationErrors` of a nested value, reported under the field
+///   * `capture_value` includes the rejected value in the errors, redacted for `#[sensitive]`
+///   * `when = f` only runs it when `f(&unvalidated)` holds, not allowed with `setters` or `patch`
+///   * `ref` takes `&T` and returns `Result<(), E>`, also run by `check(&self)` on the
+///     unvalidated struct
+///   * `groups(...)` only runs it in `validate_for::<G>()` for one of the listed group types
+///   * `severity = warn` only reports warnings in `validate_with_warnings()`
+/// * `validation_error` is set on the struct and sets the error type, `auto` generates an
+///   `AValidationError` enum with a variant per validated field, holding the error type declared
+///   with `error_type = T` on its validator, `String` by default.
+/// * `validation` is set on the struct and configures the derive:
+///   * `context = T` passes a `&T` to validators marked with `context`
+///   * `builder` generates a builder for the unvalidated struct, `builder = typestate` checks the
+///     required fields at compile time
+///   * `setters` generates `try_set_<field>` methods on the validated struct
+///   * `patch` generates a patch struct for partial updates
+///   * `partial` generates `validate_partial` yielding the valid fields of an invalid input
+///   * `sealed` requires private fields and generates getters for them
+///   * `max_errors = N` records at most N failures
+///   * `dedup_errors` records identical errors of a field once
+/// * `sanitizer` is set on a field and lists `fn(T) -> T` functions applied before its validator.
+/// * `default` is set on a field to make it optional in the unvalidated struct, either
+///   `#[default]` to fill in `Default::default()`, `#[default = literal]` or `#[default(expr)]`.
+/// * `validation_default` is the same as `default`, for structs also deriving `Default`.
+/// * `sensitive` is set on a field to redact its value in the errors.
 #[proc_macro_error]
-#[proc_macro_derive(Validated, attributes(validator, validation_error))]
+#[proc_macro_derive(
+    Validated,
+    attributes(
+        validator,
+        validation_error,
+        validation,
+        sanitizer,
+        default,
+        validation_default,
+        sensitive
+    )
+)]
 pub fn valibuk_derive(input: TokenStream) -> TokenStream {
     valibuk_core(input.into()).into()
 }